@@ -1,64 +1,274 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
-    FnArg, ItemFn, Pat, Stmt, Type, parse_macro_input, parse_quote, punctuated::Punctuated,
-    token::Comma,
+    Attribute, Expr, FnArg, GenericArgument, ItemFn, Lit, Meta, Pat, PathArguments, ReturnType,
+    Stmt, Type, parse_macro_input, parse_quote, punctuated::Punctuated, token::Comma,
 };
 
-fn is_option_type(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.last() {
-            return segment.ident == "Option";
-        }
+fn arg_name(pat: &Pat) -> String {
+    match pat {
+        Pat::Ident(ident) => ident.ident.to_string().trim_start_matches('_').to_string(),
+        other => quote!(#other).to_string(),
     }
-    false
 }
 
-fn api_args(inputs: Punctuated<FnArg, Comma>) -> Option<Stmt> {
-    let len = inputs.len();
-    if len == 0 {
+/// Renders a type the way it is written, without the token spacing.
+fn type_name(ty: &Type) -> String {
+    quote!(#ty).to_string().replace(' ', "")
+}
+
+/// Returns `T` for `Wrapper<T>`.
+fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else { return None };
+    let last = path.path.segments.last()?;
+    if last.ident != wrapper {
         return None;
     }
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
-    let mut names = Punctuated::<Box<Pat>, Comma>::new();
-    let mut types = Punctuated::<Box<Type>, Comma>::new();
-
-    for arg in inputs {
-        if let FnArg::Typed(typed) = arg {
-            names.push(typed.pat.clone());
-            types.push(typed.ty.clone());
-        }
+/// Type of the value a handler produces: `T` for `Result<T>`.
+fn result_type(output: &ReturnType) -> Type {
+    match output {
+        ReturnType::Default => parse_quote!(()),
+        ReturnType::Type(_, ty) => generic_inner(ty, "Result").unwrap_or(ty).clone(),
     }
+}
 
-    let has_option = types.iter().any(|ty| is_option_type(ty));
+/// Joins the `///` lines of the handler into one description.
+fn doc_string(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                Expr::Lit(lit) => match &lit.lit {
+                    Lit::Str(s) => Some(s.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
 
-    if has_option {
-        Some(parse_quote! {
-            let (#names,) = req.args().optional::<(#types,)>(#len)?;
+/// Extracts every parameter on its own, by position or by name, so a
+/// failing one is reported by name and position instead of as an opaque
+/// tuple error. Also returns the parameter descriptions used by
+/// `system.describe`.
+fn api_args(inputs: Punctuated<FnArg, Comma>) -> (Vec<Stmt>, Vec<proc_macro2::TokenStream>) {
+    let typed: Vec<_> = inputs
+        .into_iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(typed) => Some(typed),
+            FnArg::Receiver(_) => None,
         })
-    } else {
-        Some(parse_quote! {
-            let (#names,) = req.args().get::<(#types,)>()?;
+        .collect();
+    let names: Vec<String> = typed.iter().map(|t| arg_name(&t.pat)).collect();
+    let check: Stmt = parse_quote! {
+        req.args().check_names(&[#(#names),*])?;
+    };
+    let (stmts, params): (Vec<Stmt>, Vec<_>) = typed
+        .into_iter()
+        .enumerate()
+        .map(|(index, typed)| {
+            let pat = typed.pat;
+            let ty = typed.ty;
+            let name = arg_name(&pat);
+            let optional = generic_inner(&ty, "Option").is_some();
+            let ty_name = type_name(&ty);
+            let stmt = parse_quote! {
+                let #pat: #ty = req.args().arg::<#ty>(#index, #name)?;
+            };
+            let param = quote! {
+                crate::api_manager::ApiParam {
+                    name: #name,
+                    ty: #ty_name,
+                    optional: #optional,
+                    schema: crate::schema::arg_schema::<#ty>,
+                }
+            };
+            (stmt, param)
         })
-    }
+        .unzip();
+    (std::iter::once(check).chain(stmts).collect(), params)
 }
 
+/// Turns a function into an API handler.
+///
+/// `#[api]` handlers run on the event loop and may use `app`, `req`,
+/// `target` and `flow`. `#[api(async)]` handlers must be `async fn`s; they
+/// run on the tokio runtime with `app: AppHandle` and `req`, and reach the
+/// window through `app.run_on_main`. `#[api(stream)]` handlers are async
+/// handlers that additionally get `chunks: ChunkSink` to send their result
+/// piece by piece; what they return ends the stream.
+///
+/// Requests may pass the parameters as an array in declaration order or as
+/// an object keyed by parameter name (without a leading `_`); omitted
+/// `Option` parameters are `None` either way.
+///
+/// The function becomes a unit struct of the same name implementing
+/// `ApiHandler` or `AsyncApiHandler`, which also reports its parameters,
+/// result type and doc comment for `system.describe`. Parameter and result
+/// types must implement `schemars::JsonSchema` for the schemas reported
+/// there.
 #[proc_macro_attribute]
-pub fn api(_: TokenStream, raw: TokenStream) -> TokenStream {
+pub fn api(attr: TokenStream, raw: TokenStream) -> TokenStream {
+    let (is_async, is_stream) = match attr.to_string().as_str() {
+        "" => (false, false),
+        "async" => (true, false),
+        "stream" => (true, true),
+        other => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "unknown api option `{}`, expected `async` or `stream`",
+                    other
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
     let f = parse_macro_input!(raw as ItemFn);
+    if is_async != f.sig.asyncness.is_some() {
+        let msg = if is_stream {
+            "#[api(stream)] requires an `async fn`"
+        } else if is_async {
+            "#[api(async)] requires an `async fn`"
+        } else {
+            "an `async fn` needs #[api(async)] or #[api(stream)]"
+        };
+        return syn::Error::new_spanned(&f.sig, msg)
+            .to_compile_error()
+            .into();
+    }
+    let attrs = f.attrs;
+    let doc = doc_string(&attrs);
+    let vis = f.vis;
     let name = f.sig.ident;
     let output = f.sig.output;
+    let result_ty = result_type(&output);
+    let result = type_name(&result_ty);
     let body = f.block.stmts;
-    let args_stmt = api_args(f.sig.inputs);
-    let expanded = quote! {
-        fn #name(
-            app: std::sync::Arc<crate::core::App>,
-            req: crate::api_manager::ApiRequest,
-            target: &crate::utils::FrameWindowTarget,
-            flow: &mut tao::event_loop::ControlFlow,
-        ) #output {
-            #args_stmt
-            #(#body)*
+    let (args_stmts, params) = api_args(f.sig.inputs);
+    let describe = quote! {
+        fn params(&self) -> &'static [crate::api_manager::ApiParam] {
+            &[#(#params),*]
+        }
+
+        fn result(&self) -> &'static str {
+            #result
+        }
+
+        fn result_schema(&self) -> serde_json::Value {
+            crate::schema::result_schema::<#result_ty>()
+        }
+
+        fn doc(&self) -> &'static str {
+            #doc
+        }
+    };
+    let expanded = if is_stream {
+        quote! {
+            #(#attrs)*
+            #[allow(non_camel_case_types)]
+            #vis struct #name;
+
+            impl crate::api_manager::AsyncApiHandler for #name {
+                #describe
+
+                fn stream(&self) -> bool {
+                    true
+                }
+
+                fn call(
+                    &self,
+                    app: crate::api_manager::AppHandle,
+                    req: crate::api_manager::ApiRequest,
+                    chunks: crate::api_manager::ChunkSink,
+                ) -> std::pin::Pin<
+                    Box<dyn std::future::Future<Output = anyhow::Result<crate::binary::Payload>> + Send>,
+                > {
+                    async fn handler(
+                        app: crate::api_manager::AppHandle,
+                        req: crate::api_manager::ApiRequest,
+                        chunks: crate::api_manager::ChunkSink,
+                    ) #output {
+                        #(#args_stmts)*
+                        #(#body)*
+                    }
+                    Box::pin(async move {
+                        crate::api_manager::to_result(handler(app, req, chunks).await?)
+                    })
+                }
+            }
+        }
+    } else if is_async {
+        quote! {
+            #(#attrs)*
+            #[allow(non_camel_case_types)]
+            #vis struct #name;
+
+            impl crate::api_manager::AsyncApiHandler for #name {
+                #describe
+
+                fn call(
+                    &self,
+                    app: crate::api_manager::AppHandle,
+                    req: crate::api_manager::ApiRequest,
+                    _chunks: crate::api_manager::ChunkSink,
+                ) -> std::pin::Pin<
+                    Box<dyn std::future::Future<Output = anyhow::Result<crate::binary::Payload>> + Send>,
+                > {
+                    async fn handler(
+                        app: crate::api_manager::AppHandle,
+                        req: crate::api_manager::ApiRequest,
+                    ) #output {
+                        #(#args_stmts)*
+                        #(#body)*
+                    }
+                    Box::pin(async move { crate::api_manager::to_result(handler(app, req).await?) })
+                }
+            }
+        }
+    } else {
+        quote! {
+            #(#attrs)*
+            #[allow(non_camel_case_types)]
+            #vis struct #name;
+
+            impl crate::api_manager::ApiHandler for #name {
+                #describe
+
+                fn call(
+                    &self,
+                    app: std::sync::Arc<crate::core::App>,
+                    req: crate::api_manager::ApiRequest,
+                    target: &crate::utils::FrameWindowTarget,
+                    flow: &mut tao::event_loop::ControlFlow,
+                ) -> anyhow::Result<crate::binary::Payload> {
+                    fn handler(
+                        app: std::sync::Arc<crate::core::App>,
+                        req: crate::api_manager::ApiRequest,
+                        target: &crate::utils::FrameWindowTarget,
+                        flow: &mut tao::event_loop::ControlFlow,
+                    ) #output {
+                        #(#args_stmts)*
+                        #(#body)*
+                    }
+                    crate::api_manager::to_result(handler(app, req, target, flow)?)
+                }
+            }
         }
     };
     expanded.into()
@@ -1,5 +1,6 @@
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use tao::dpi::{
@@ -13,14 +14,14 @@ use tao::window::{
 };
 use wry::Rect;
 
-#[derive(Deserialize, Clone, Debug, Serialize)]
+#[derive(Deserialize, Clone, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum UnitType {
     Logical,
     Physical,
 }
 
-#[derive(Deserialize, Clone, Debug, Serialize)]
+#[derive(Deserialize, Clone, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
     pub x: Option<i32>,
@@ -28,7 +29,7 @@ pub struct Position {
     pub unit: UnitType,
 }
 
-#[derive(Deserialize, Clone, Debug, Serialize)]
+#[derive(Deserialize, Clone, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Size {
     pub width: Option<i32>,
@@ -36,7 +37,7 @@ pub struct Size {
     pub unit: UnitType,
 }
 
-#[derive(Deserialize, Clone, Debug, Serialize)]
+#[derive(Deserialize, Clone, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WebViewBounds {
     pub position: Position,
@@ -112,7 +113,7 @@ impl From<WebViewBounds> for Rect {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum CursorIcon {
     Default,
@@ -194,7 +195,7 @@ impl From<CursorIcon> for TaoCursorIcon {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ProgressState {
     None,
@@ -216,7 +217,7 @@ impl From<ProgressState> for TaoProgressState {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ProgressBarState {
     pub progress: Option<u64>,
@@ -234,7 +235,7 @@ impl From<ProgressBarState> for TaoProgressBarState {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum Theme {
     Light,
@@ -250,7 +251,7 @@ impl From<Theme> for TaoTheme {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum UserAttentionType {
     Critical,
@@ -266,7 +267,7 @@ impl From<UserAttentionType> for TaoUserAttentionType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ByteIcon {
     pub rgba: String, // Base64-encoded
@@ -281,7 +282,7 @@ impl From<ByteIcon> for TaoWindowIcon {
     }
 }
 #[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Icon {
     pub path: String,
@@ -297,7 +298,7 @@ impl Icon {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Dimensions {
     /// The width of the size.
@@ -307,7 +308,7 @@ pub struct Dimensions {
 }
 
 /// Serde-kompatibles Constraints-Objekt
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowSizeConstraints {
     pub min_width: Option<f64>,
@@ -363,7 +364,7 @@ impl From<WindowSizeConstraints> for TaoWindowSizeConstraints {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitorVideoMode {
     /// The size of the video mode.
@@ -374,7 +375,7 @@ pub struct MonitorVideoMode {
     pub refresh_rate: u16,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Monitor {
     /// The name of the monitor.
@@ -389,7 +390,7 @@ pub struct Monitor {
     pub video_modes: Vec<MonitorVideoMode>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitorPosition {
     /// The x position.
@@ -399,7 +400,7 @@ pub struct MonitorPosition {
 }
 
 #[allow(dead_code)]
-#[derive(Deserialize, Clone, Debug, Default)]
+#[derive(Deserialize, Clone, Debug, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowOptions {
     pub always_on_bottom: Option<bool>,
@@ -425,10 +426,11 @@ pub struct WindowOptions {
     pub visible: Option<bool>,
     pub visible_on_all_workspaces: Option<bool>,
     pub window_icon: Option<Icon>,
+    #[serde(default)]
     pub webview: WebViewOptions,
 }
 #[allow(dead_code)]
-#[derive(Deserialize, Clone, Debug, Default)]
+#[derive(Deserialize, Clone, Debug, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WebViewOptions {
     pub label: Option<String>,
@@ -451,9 +453,23 @@ pub struct WebViewOptions {
     pub zoom_hotkeys: Option<bool>,
     pub background_throttling: Option<bool>,
     pub back_forward_navigation_gestures: Option<bool>,
+    pub asset_symlinks: Option<SymlinkPolicy>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+/// How the `wry://` protocol treats symlinks inside the asset folder.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SymlinkPolicy {
+    /// Follow symlinks as long as their target stays inside the folder.
+    #[default]
+    Contained,
+    /// Follow every symlink, also out of the folder.
+    Follow,
+    /// Refuse every path running through a symlink.
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Color {
     /// Rotanteil (0–255)
@@ -512,7 +528,7 @@ impl From<Color> for (u8, u8, u8, u8) {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 /// Platform-specific window effects
 pub enum WindowEffect {
@@ -541,7 +557,7 @@ pub enum WindowEffect {
     Acrylic,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum WindowEffectState {
     /// Make window effect state follow the window's active state
@@ -554,7 +570,7 @@ pub enum WindowEffectState {
 
 /// The window effects configuration object
 #[skip_serializing_none]
-#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct WindowEffectsConfig {
     /// List of Window effects to apply to the Window.
@@ -3,7 +3,7 @@
 # SPDX-License-Identifier: MIT
 
 import multiprocessing
-from typing import Any
+from typing import Any, Callable
 
 __version__: str
 """The package version as defined in `Cargo.toml`, modified to match python's versioning semantics."""
@@ -13,8 +13,51 @@ def create_webframe(
     sock_cfg: str | None,
     uds_name: str,
     close_event: multiprocessing.Event,  # type: ignore
+    server_cfg: str | None = None,
 ) -> Any: ...
 async def send_event_over_platform(
     name: str,
     message: str,
+    token: str | None = None,
 ) -> Any: ...
+
+class ResponseStream:
+    """Raw frames of a streamed response: chunks, then the final response."""
+
+    def __aiter__(self) -> ResponseStream: ...
+    async def __anext__(self) -> list[Any]: ...
+
+class IpcClient:
+    """Persistent, pooled client for the frame process' IPC server."""
+
+    def __init__(
+        self,
+        name: str,
+        pool_size: int = 1,
+        token: str | None = None,
+        protocol: int = 1,
+        codec: str = "json",
+        max_frame_size: int = 16 * 1024 * 1024,
+    ) -> None: ...
+    async def call(
+        self,
+        method: str,
+        args: str | None = None,
+        timeout: float | None = None,
+        label: str | None = None,
+    ) -> Any: ...
+    async def stream(
+        self,
+        method: str,
+        args: str | None = None,
+        timeout: float | None = None,
+        label: str | None = None,
+    ) -> ResponseStream: ...
+    async def server_info(self) -> dict[str, Any]: ...
+    async def subscribe(
+        self,
+        topics: list[str],
+        callback: Callable[[str, str | None, Any], None],
+    ) -> Any: ...
+    async def unsubscribe(self, topics: list[str] | None = None) -> Any: ...
+    async def close(self) -> None: ...
@@ -64,18 +64,20 @@ class ControlCenterAPI:
         :return: Notification metadata as a dictionary, or ``None`` if unsupported.
         :rtype: Optional[dict[str, Any]]
         """
-        args = [
-            summary,
-            body,
-            app_id,
-            appname,
-            icon,
-            auto_icon,
-            image_path,
-            sound_name,
-            subtitle,
-            timeout,
-            notification_id,
-            action,
-        ]
+        args = {
+            "summary": summary,
+            "body": body,
+            "app_id": app_id,
+            "appname": appname,
+            "icon": icon,
+            "auto_icon": auto_icon,
+            "image_path": image_path,
+            "sound_name": sound_name,
+            "subtitle": subtitle,
+            "timeout": timeout,
+            "id": notification_id,
+            "action": action,
+        }
+        # sent by name, so unset options can be dropped instead of padded
+        args = {key: value for key, value in args.items() if value is not None}
         return await event_register("controlcenter.notification", args=args)
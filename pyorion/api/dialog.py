@@ -17,10 +17,20 @@ MessageLevel = Literal["info", "warning", "error"]
 
 
 class DialogAPI:
-    """Asynchronous API wrapper for dialog operations."""
+    """Asynchronous API wrapper for dialog operations.
 
-    def __init__(self) -> None:
-        """Initialize a new :class:`DialogAPI` instance."""
+    Dialogs wait for the user, so their requests carry no deadline.
+    Arguments are sent by name, so optional ones may be left out.
+    """
+
+    def __init__(self, label: str | None = None) -> None:
+        """Initialize a new :class:`DialogAPI` instance.
+
+        :param label: Label of the target window. ``None`` addresses
+                      the main window.
+        :type label: str | None
+        """
+        self.label = label
 
     async def show_message(
         self,
@@ -40,8 +50,10 @@ class DialogAPI:
         :return: Nothing.
         :rtype: None
         """
-        args = [title, content, level]
-        return await event_register("dialog.showMessage", args=args)
+        args = {"title": title, "content": content, "level": level}
+        return await event_register(
+            "dialog.showMessage", args=args, label=self.label, timeout=None
+        )
 
     async def pick_file(
         self,
@@ -58,8 +70,10 @@ class DialogAPI:
         :return: Information about the selected file.
         :rtype: dict[str, Any]
         """
-        args = [filters, start_dir]
-        return await event_register("dialog.pickFile", args=args)
+        args = {"filters": filters, "start_dir": start_dir}
+        return await event_register(
+            "dialog.pickFile", args=args, label=self.label, timeout=None
+        )
 
     async def pick_files(
         self,
@@ -76,8 +90,10 @@ class DialogAPI:
         :return: Information about the selected files.
         :rtype: dict[str, Any]
         """
-        args = [filters, start_dir]
-        return await event_register("dialog.pickFiles", args=args)
+        args = {"filters": filters, "start_dir": start_dir}
+        return await event_register(
+            "dialog.pickFiles", args=args, label=self.label, timeout=None
+        )
 
     async def pick_dir(
         self,
@@ -91,8 +107,10 @@ class DialogAPI:
         :return: Information about the selected directory.
         :rtype: dict[str, Any]
         """
-        args = [start_dir]
-        return await event_register("dialog.pickDir", args=args)
+        args = {"start_dir": start_dir}
+        return await event_register(
+            "dialog.pickDir", args=args, label=self.label, timeout=None
+        )
 
     async def pick_dirs(
         self,
@@ -106,8 +124,10 @@ class DialogAPI:
         :return: Information about the selected directories.
         :rtype: dict[str, Any]
         """
-        args = [start_dir]
-        return await event_register("dialog.pickDirs", args=args)
+        args = {"start_dir": start_dir}
+        return await event_register(
+            "dialog.pickDirs", args=args, label=self.label, timeout=None
+        )
 
     async def save_file(
         self,
@@ -124,5 +144,7 @@ class DialogAPI:
         :return: Information about the saved file.
         :rtype: dict[str, Any]
         """
-        args = [filters, start_dir]
-        return await event_register("dialog.saveFile", args=args)
+        args = {"filters": filters, "start_dir": start_dir}
+        return await event_register(
+            "dialog.saveFile", args=args, label=self.label, timeout=None
+        )
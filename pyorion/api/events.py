@@ -0,0 +1,63 @@
+# Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+# SPDX-License-Identifier: Apache-2.0
+# SPDX-License-Identifier: MIT
+
+"""Events API - Subscribe to events pushed by the PyOrion event loop.
+
+Window events are delivered with the label of the window they belong to.
+
+Topics
+------
+
+* ``window.resized`` — ``{"width", "height"}`` in physical pixels.
+* ``window.moved`` — ``{"x", "y"}`` in physical pixels.
+* ``window.focused`` — ``{"focused"}``, ``False`` when focus is lost.
+* ``window.themeChanged`` — ``{"theme"}``, ``"light"`` or ``"dark"``.
+* ``window.fileDropped`` — ``{"path"}`` of the dropped file.
+* ``window.keyboardInput`` — ``{"key", "code", "text", "pressed", "repeat"}``.
+* ``window.closeRequested`` — no payload.
+* ``*`` — every topic.
+"""
+
+from pyorion.runtime.runtime_handle import (
+    EventHandler,
+    EventModel,
+    subscribe_events,
+    unsubscribe_events,
+)
+
+
+__all__ = ["EventHandler", "EventModel", "off", "on"]
+
+
+async def on(topics: str | list[str], handler: EventHandler) -> list[str]:
+    """Register ``handler`` for one or more event topics.
+
+    :param topics: A topic or list of topics.
+    :type topics: str | list[str]
+    :param handler: Callable or coroutine function receiving an
+                    :class:`EventModel`.
+    :type handler: EventHandler
+    :return: All subscribed topics.
+    :rtype: list[str]
+    """
+    if isinstance(topics, str):
+        topics = [topics]
+    return await subscribe_events(topics, handler)
+
+
+async def off(
+    topics: str | list[str] | None = None, handler: EventHandler | None = None
+) -> list[str]:
+    """Remove handlers for event topics.
+
+    :param topics: A topic, list of topics or ``None`` for all topics.
+    :type topics: str | list[str] | None
+    :param handler: The handler to remove, ``None`` removes all handlers.
+    :type handler: EventHandler | None
+    :return: Topics that are still subscribed.
+    :rtype: list[str]
+    """
+    if isinstance(topics, str):
+        topics = [topics]
+    return await unsubscribe_events(topics, handler)
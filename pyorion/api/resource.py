@@ -0,0 +1,97 @@
+# Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+# SPDX-License-Identifier: Apache-2.0
+# SPDX-License-Identifier: MIT
+
+"""Resource API - Access to the file system.
+
+``list_recursive()`` and ``search()`` return all paths at once. Directory
+walks, searches and file reads can be far too large for a single response,
+so their ``*_stream`` variants and ``read_stream()`` have the Rust handlers
+send them in chunks, which these functions yield as they arrive. Breaking
+out of the loop stops the handler.
+"""
+
+from typing import AsyncIterator
+
+from pyorion.runtime.runtime_handle import event_register, event_stream
+
+
+__all__ = [
+    "list_recursive",
+    "list_recursive_stream",
+    "read_stream",
+    "search",
+    "search_stream",
+]
+
+
+async def list_recursive(path: str) -> list[str]:
+    """Walk a directory tree and return its files.
+
+    :param path: Directory to walk.
+    :type path: str
+    :return: Paths of all files below ``path``.
+    :rtype: list[str]
+    """
+    return await event_register("resource.list_recursive", [path])
+
+
+async def list_recursive_stream(path: str) -> AsyncIterator[list[str]]:
+    """Walk a directory tree and yield its files in batches.
+
+    :param path: Directory to walk.
+    :type path: str
+    :return: Async iterator over lists of file paths.
+    :rtype: AsyncIterator[list[str]]
+    """
+    async for paths in event_stream("resource.list_recursive_stream", [path]):
+        yield paths
+
+
+async def search(path: str, query: str) -> list[str]:
+    """Return the files below ``path`` whose name contains ``query``.
+
+    The match ignores case.
+
+    :param path: Directory to search.
+    :type path: str
+    :param query: Part of the file name to look for.
+    :type query: str
+    :return: Paths of the matching files.
+    :rtype: list[str]
+    """
+    return await event_register("resource.search", [path, query])
+
+
+async def search_stream(path: str, query: str) -> AsyncIterator[list[str]]:
+    """Yield the files below ``path`` whose name contains ``query``.
+
+    The match ignores case. Paths arrive in batches.
+
+    :param path: Directory to search.
+    :type path: str
+    :param query: Part of the file name to look for.
+    :type query: str
+    :return: Async iterator over lists of matching paths.
+    :rtype: AsyncIterator[list[str]]
+    """
+    async for paths in event_stream("resource.search_stream", [path, query]):
+        yield paths
+
+
+async def read_stream(
+    path: str, chunk_size: int | None = None
+) -> AsyncIterator[bytes | str]:
+    """Read a file piece by piece.
+
+    :param path: File to read.
+    :type path: str
+    :param chunk_size: Bytes per chunk, 64 KiB when ``None``; at most
+                       8 MiB, larger values are rejected.
+    :type chunk_size: int | None
+    :return: Async iterator over the chunks: ``bytes`` with the MessagePack
+             codec, base64 strings with JSON.
+    :rtype: AsyncIterator[bytes | str]
+    """
+    async for chunk in event_stream("resource.read_stream", [path, chunk_size]):
+        yield chunk
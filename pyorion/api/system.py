@@ -0,0 +1,90 @@
+# Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+# SPDX-License-Identifier: Apache-2.0
+# SPDX-License-Identifier: MIT
+
+"""System API - Introspection of the methods exposed by the event loop.
+
+``describe()`` returns, per method name, its parameters with their Rust
+types and JSON Schemas, schemas for passing ``args`` as an array or by
+name as an object, and the result type. It can drive argument validation
+or generated stubs. ``server_info()`` returns what the server reported
+when the connection was opened: its protocol and library versions, the
+API namespaces and optional features. ``batch()`` runs several calls in
+one event-loop turn, so a layout is applied without a repaint in between.
+"""
+
+from typing import Any, cast
+
+from pyorion.runtime.runtime_handle import (
+    ApiError,
+    ApiResponseModel,
+    event_register,
+    get_client,
+)
+from pyorion.utils import normalize_args
+
+
+__all__ = ["batch", "describe", "server_info"]
+
+
+async def describe() -> dict[str, Any]:
+    """List every registered method with its argument schema.
+
+    :return: ``{"methods": {name: {"async", "stream", "doc", "params",
+             "argsSchema", "kwargsSchema", "result"}}}``.
+    :rtype: dict[str, Any]
+    """
+    return await event_register("system.describe")
+
+
+async def server_info() -> dict[str, Any]:
+    """Return what the server reported in the connection handshake.
+
+    :return: ``{"protocol", "version", "namespaces", "features"}``.
+    :rtype: dict[str, Any]
+    """
+    return cast(dict[str, Any], await get_client().server_info())
+
+
+async def batch(
+    calls: list[tuple[Any, ...]],
+    stop_on_error: bool = False,
+    label: str | None = None,
+) -> list[Any]:
+    """Run event-loop calls in order, within a single dispatch.
+
+    Each call is ``(method,)``, ``(method, args)`` or ``(method, args,
+    label)``; ``args`` is normalized like in :func:`event_register`.
+    Async methods and ``system.batch`` itself cannot be batched.
+
+    :param calls: The calls to run.
+    :type calls: list[tuple[Any, ...]]
+    :param stop_on_error: Check every call before running the first one;
+                          if one names an unknown or async method, none
+                          runs. Otherwise skip the calls after the first
+                          failing one. Skipped calls fail with
+                          :class:`RequestCancelledError`; calls already
+                          run are not undone.
+    :type stop_on_error: bool
+    :param label: Window targeted by calls without their own label.
+    :type label: str | None
+    :return: One entry per call: its result, or the :class:`ApiError` it
+             failed with.
+    :rtype: list[Any]
+    """
+    payload = []
+    for method, *rest in calls:
+        args = normalize_args(rest[0] if rest else None)
+        call: dict[str, Any] = {"method": method, "args": args}
+        if len(rest) > 1 and rest[1] is not None:
+            call["label"] = rest[1]
+        payload.append(call)
+    responses = await event_register(
+        "system.batch", args=[payload, stop_on_error], label=label
+    )
+    results: list[Any] = []
+    for arr in responses:
+        resp = ApiResponseModel.from_array(arr)
+        ok = resp.code == 0
+        results.append(resp.result if ok else ApiError.from_response(resp))
+    return results
@@ -4,18 +4,26 @@
 
 """WebView API - Control operations for PyOrion WebView instances.
 
-Wrapper around the Rust event loop backend to manage developer tools
-and WebView state.
+Wrapper around the Rust event loop backend to manage developer tools,
+WebView state and script evaluation.
 """
 
+from typing import Any
+
 from pyorion.runtime.runtime_handle import event_register
 
 
 class WebView:
     """High-level asynchronous wrapper for controlling a WebView instance."""
 
-    def __init__(self) -> None:
-        """Initialize a new :class:`WebView` handle."""
+    def __init__(self, label: str | None = None) -> None:
+        """Initialize a new :class:`WebView` handle.
+
+        :param label: Label of the target window. ``None`` addresses
+                      the main window.
+        :type label: str | None
+        """
+        self.label = label
 
     async def is_devtools_open(self) -> bool:
         """Check if the WebView developer tools window is currently open.
@@ -23,7 +31,9 @@ class WebView:
         :return: ``True`` if DevTools is open, otherwise ``False``.
         :rtype: bool
         """
-        return await event_register("webview.isDevtoolsOpen", None, result_type=bool)
+        return await event_register(
+            "webview.isDevtoolsOpen", None, result_type=bool, label=self.label
+        )
 
     async def open_devtools(self) -> bool:
         """Open the WebView developer tools window.
@@ -31,7 +41,9 @@ class WebView:
         :return: ``True`` if the operation succeeded, otherwise ``False``.
         :rtype: bool
         """
-        return await event_register("webview.openDevtools", None, result_type=bool)
+        return await event_register(
+            "webview.openDevtools", None, result_type=bool, label=self.label
+        )
 
     async def close_devtools(self) -> bool:
         """Close the WebView developer tools window.
@@ -39,4 +51,68 @@ class WebView:
         :return: ``True`` if the operation succeeded, otherwise ``False``.
         :rtype: bool
         """
-        return await event_register("webview.closeDevtools", None, result_type=bool)
+        return await event_register(
+            "webview.closeDevtools", None, result_type=bool, label=self.label
+        )
+
+    async def emit(self, event: str, payload: Any = None) -> None:
+        """Push an event to the listeners of the page.
+
+        Pages receive it through ``listen(event, handler)`` and as a
+        ``pyorion:<event>`` ``CustomEvent`` on ``window``.
+
+        :param event: Event name.
+        :type event: str
+        :param payload: JSON-safe data handed to the listeners.
+        :type payload: Any
+        """
+        await event_register("webview.emit", [event, payload], label=self.label)
+
+    async def eval(self, script: str) -> None:
+        """Run JavaScript in the page without waiting for it to finish.
+
+        :param script: Script to evaluate.
+        :type script: str
+        """
+        await event_register("webview.eval", [script], label=self.label)
+
+    async def eval_with_result(self, script: str, timeout: float = 10.0) -> Any:
+        """Run JavaScript in the page and return the value of its last
+        expression.
+
+        The value is passed through ``JSON.stringify``, so DOM nodes and
+        functions do not survive; ``undefined`` reads as ``None``.
+
+        :param script: Script to evaluate.
+        :type script: str
+        :param timeout: Seconds to wait for the page, e.g. when the script
+                        throws and never answers.
+        :type timeout: float
+        :return: The decoded result.
+        :rtype: Any
+        :raises ApiTimeoutError: If the page did not answer in time.
+        """
+        return await event_register(
+            "webview.evalWithResult",
+            [script, int(timeout * 1000)],
+            label=self.label,
+            # the page's deadline has to run out first
+            timeout=timeout + 1.0,
+        )
+
+
+async def emit(event: str, payload: Any = None, target_label: str | None = None) -> None:
+    """Push an event to the listeners of every page, or of one.
+
+    :param event: Event name.
+    :type event: str
+    :param payload: JSON-safe data handed to the listeners.
+    :type payload: Any
+    :param target_label: Label of the only window to notify, ``None`` for
+                         all open windows.
+    :type target_label: str | None
+    """
+    if target_label is None:
+        await event_register("webview.broadcast", [event, payload])
+    else:
+        await WebView(target_label).emit(event, payload)
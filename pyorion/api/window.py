@@ -24,6 +24,7 @@ from pyorion.setup.types import (
     WindowEffect,
     WindowEffectsConfig,
     WindowEffectState,
+    WindowOptions,
     WindowSizeConstraints,
 )
 
@@ -36,8 +37,54 @@ class Window:
     Each call delegates to the Rust event loop backend.
     """
 
-    def __init__(self):
-        """Initialize a new Window instance."""
+    def __init__(self, label: str | None = None):
+        """Initialize a new Window instance.
+
+        :param label: Label of the window to control. ``None`` addresses
+                      the main window.
+        :type label: str | None
+        """
+        self.label = label
+
+    @classmethod
+    async def create(cls, options: WindowOptions) -> "Window":
+        """Open a new window and return a handle bound to it.
+
+        The window label is taken from ``options.webview.label``; a unique
+        one is generated by the backend when it is missing.
+
+        :param options: Configuration of the new window and its webview.
+        :type options: WindowOptions
+        :return: Handle addressing the created window.
+        :rtype: Window
+        """
+        label = await event_register(
+            "window.create",
+            options.model_dump(by_alias=True, exclude_none=True),
+            result_type=str,
+        )
+        return cls(label)
+
+    @staticmethod
+    async def list() -> list[str]:
+        """Return the labels of all open windows in creation order.
+
+        :return: Window labels.
+        :rtype: list[str]
+        """
+        return await event_register("window.list", None)
+
+    async def close(self) -> bool:
+        """Close this window.
+
+        The runtime only shuts down once the last window is closed.
+
+        :return: ``True`` if the window was closed.
+        :rtype: bool
+        """
+        return await event_register(
+            "window.close", None, result_type=bool, label=self.label
+        )
 
     async def set_window_effect(
         self,
@@ -71,6 +118,7 @@ class Window:
             "window.set_window_effect",
             config.model_dump(by_alias=True, exclude_none=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_visible(self, visible: bool) -> bool:
@@ -86,7 +134,9 @@ class Window:
             - Android: Unsupported
             - iOS: Must be called on the main thread
         """
-        return await event_register("window.set_visible", visible, result_type=bool)
+        return await event_register(
+            "window.set_visible", visible, result_type=bool, label=self.label
+        )
 
     async def set_title(self, title: str) -> bool:
         """Set the title of the window.
@@ -100,7 +150,9 @@ class Window:
         Platform-specific:
             - iOS / Android: Unsupported → always returns ``False``
         """
-        return await event_register("window.set_title", title, result_type=bool)
+        return await event_register(
+            "window.set_title", title, result_type=bool, label=self.label
+        )
 
     async def get_title(self) -> str:
         """Get the current title of the window.
@@ -113,7 +165,9 @@ class Window:
         Platform-specific:
             - iOS / Android: Unsupported → returns empty string
         """
-        return await event_register("window.get_title", {}, result_type=str)
+        return await event_register(
+            "window.get_title", {}, result_type=str, label=self.label
+        )
 
     async def scale_factor(self) -> float:
         """Return the display scale factor of the window.
@@ -127,7 +181,9 @@ class Window:
             - Android: Always returns ``1.0``
             - iOS: Must be called on the main thread
         """
-        return await event_register("window.scale_factor", {}, result_type=float)
+        return await event_register(
+            "window.scale_factor", {}, result_type=float, label=self.label
+        )
 
     async def set_always_on_bottom(self, always: bool) -> bool:
         """Set whether the window is always kept on bottom.
@@ -135,7 +191,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_always_on_bottom``.
         """
         return await event_register(
-            "window.set_always_on_bottom", always, result_type=bool
+            "window.set_always_on_bottom", always, result_type=bool, label=self.label
         )
 
     async def set_always_on_top(self, always: bool) -> bool:
@@ -144,7 +200,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_always_on_top``.
         """
         return await event_register(
-            "window.set_always_on_top", always, result_type=bool
+            "window.set_always_on_top", always, result_type=bool, label=self.label
         )
 
     async def set_background_color(self, color: RGBA | None) -> bool:
@@ -156,6 +212,7 @@ class Window:
             "window.set_background_color",
             None if color is None else color.as_tuple(),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_closable(self, closable: bool) -> bool:
@@ -163,7 +220,9 @@ class Window:
 
         Wrapper for ``tao::window::Window::set_closable``.
         """
-        return await event_register("window.set_closable", closable, result_type=bool)
+        return await event_register(
+            "window.set_closable", closable, result_type=bool, label=self.label
+        )
 
     async def set_content_protection(self, enabled: bool) -> bool:
         """Enable or disable content protection.
@@ -171,7 +230,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_content_protection``.
         """
         return await event_register(
-            "window.set_content_protection", enabled, result_type=bool
+            "window.set_content_protection", enabled, result_type=bool, label=self.label
         )
 
     async def set_cursor_grab(self, grab: bool) -> bool:
@@ -179,14 +238,18 @@ class Window:
 
         Wrapper for ``tao::window::Window::set_cursor_grab``.
         """
-        return await event_register("window.set_cursor_grab", grab, result_type=bool)
+        return await event_register(
+            "window.set_cursor_grab", grab, result_type=bool, label=self.label
+        )
 
     async def set_cursor_icon(self, cursor: CursorIcon) -> bool:
         """Set the cursor icon.
 
         Wrapper for ``tao::window::Window::set_cursor_icon``.
         """
-        return await event_register("window.set_cursor_icon", cursor, result_type=bool)
+        return await event_register(
+            "window.set_cursor_icon", cursor, result_type=bool, label=self.label
+        )
 
     async def set_cursor_position(self, position: Position) -> bool:
         """Set the cursor position in window coordinates.
@@ -197,6 +260,7 @@ class Window:
             "window.set_cursor_position",
             position.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_cursor_visible(self, visible: bool) -> bool:
@@ -205,7 +269,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_cursor_visible``.
         """
         return await event_register(
-            "window.set_cursor_visible", visible, result_type=bool
+            "window.set_cursor_visible", visible, result_type=bool, label=self.label
         )
 
     async def set_decorations(self, decorations: bool) -> bool:
@@ -214,7 +278,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_decorations``.
         """
         return await event_register(
-            "window.set_decorations", decorations, result_type=bool
+            "window.set_decorations", decorations, result_type=bool, label=self.label
         )
 
     async def set_focus(self) -> bool:
@@ -222,14 +286,18 @@ class Window:
 
         Wrapper for ``tao::window::Window::set_focus``.
         """
-        return await event_register("window.set_focus", {}, result_type=bool)
+        return await event_register(
+            "window.set_focus", {}, result_type=bool, label=self.label
+        )
 
     async def set_focusable(self, focusable: bool) -> bool:
         """Set focusable state.
 
         Wrapper for ``tao::window::Window::set_focusable``.
         """
-        return await event_register("window.set_focusable", focusable, result_type=bool)
+        return await event_register(
+            "window.set_focusable", focusable, result_type=bool, label=self.label
+        )
 
     async def get_available_monitors(self) -> list[Monitor]:
         """Return the list of available monitors.
@@ -237,7 +305,10 @@ class Window:
         Wrapper for ``tao::window::Window::available_monitors``.
         """
         return await event_register(
-            "window.get_available_monitors", {}, result_type=list[Monitor]
+            "window.get_available_monitors",
+            {},
+            result_type=list[Monitor],
+            label=self.label,
         )
 
     async def set_fullscreen(self, fullscreen: bool) -> bool:
@@ -246,7 +317,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_fullscreen``.
         """
         return await event_register(
-            "window.set_fullscreen", fullscreen, result_type=bool
+            "window.set_fullscreen", fullscreen, result_type=bool, label=self.label
         )
 
     async def set_ignore_cursor_events(self, ignore: bool) -> bool:
@@ -255,7 +326,10 @@ class Window:
         Wrapper for ``tao::window::Window::set_ignore_cursor_events``.
         """
         return await event_register(
-            "window.set_ignore_cursor_events", ignore, result_type=bool
+            "window.set_ignore_cursor_events",
+            ignore,
+            result_type=bool,
+            label=self.label,
         )
 
     async def set_ime_position(self, position: Position) -> bool:
@@ -267,6 +341,7 @@ class Window:
             "window.set_ime_position",
             position.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_progress_bar(self, progress: ProgressBarState) -> bool:
@@ -278,6 +353,7 @@ class Window:
             "window.set_progress_bar",
             progress.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_inner_size(self, size: Size) -> bool:
@@ -286,7 +362,10 @@ class Window:
         Wrapper for ``tao::window::Window::set_inner_size``.
         """
         return await event_register(
-            "window.set_inner_size", size.model_dump(by_alias=True), result_type=bool
+            "window.set_inner_size",
+            size.model_dump(by_alias=True),
+            result_type=bool,
+            label=self.label,
         )
 
     async def set_inner_size_constraints(
@@ -300,6 +379,7 @@ class Window:
             "window.set_inner_size_constraints",
             constraints.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_max_inner_size(self, size: Size) -> bool:
@@ -311,6 +391,7 @@ class Window:
             "window.set_max_inner_size",
             size.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_maximizable(self, maximizable: bool) -> bool:
@@ -319,7 +400,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_maximizable``.
         """
         return await event_register(
-            "window.set_maximizable", maximizable, result_type=bool
+            "window.set_maximizable", maximizable, result_type=bool, label=self.label
         )
 
     async def set_minimized(self, minimized: bool) -> bool:
@@ -327,7 +408,9 @@ class Window:
 
         Wrapper for ``tao::window::Window::set_minimized``.
         """
-        return await event_register("window.set_minimized", minimized, result_type=bool)
+        return await event_register(
+            "window.set_minimized", minimized, result_type=bool, label=self.label
+        )
 
     async def set_min_inner_size(self, size: Size) -> bool:
         """Set minimum inner size.
@@ -338,6 +421,7 @@ class Window:
             "window.set_min_inner_size",
             size.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_minimizable(self, minimizable: bool) -> bool:
@@ -346,7 +430,7 @@ class Window:
         Wrapper for ``tao::window::Window::set_minimizable``.
         """
         return await event_register(
-            "window.set_minimizable", minimizable, result_type=bool
+            "window.set_minimizable", minimizable, result_type=bool, label=self.label
         )
 
     async def set_outer_position(self, position: Position) -> bool:
@@ -358,6 +442,7 @@ class Window:
             "window.set_outer_position",
             position.model_dump(by_alias=True),
             result_type=bool,
+            label=self.label,
         )
 
     async def set_theme(self, theme: Theme) -> bool:
@@ -365,7 +450,9 @@ class Window:
 
         Wrapper for ``tao::window::Window::set_theme``.
         """
-        return await event_register("window.set_theme", theme, result_type=bool)
+        return await event_register(
+            "window.set_theme", theme, result_type=bool, label=self.label
+        )
 
     async def set_visible_on_all_workspaces(self, visible: bool) -> bool:
         """Set whether the window is visible on all workspaces.
@@ -373,7 +460,10 @@ class Window:
         Wrapper for ``tao::window::Window::set_visible_on_all_workspaces``.
         """
         return await event_register(
-            "window.set_visible_on_all_workspaces", visible, result_type=bool
+            "window.set_visible_on_all_workspaces",
+            visible,
+            result_type=bool,
+            label=self.label,
         )
 
     async def inner_size(self) -> Dimensions:
@@ -381,14 +471,18 @@ class Window:
 
         Wrapper for ``tao::window::Window::inner_size``.
         """
-        return await event_register("window.inner_size", {}, result_type=Dimensions)
+        return await event_register(
+            "window.inner_size", {}, result_type=Dimensions, label=self.label
+        )
 
     async def outer_size(self) -> Dimensions:
         """Return outer size.
 
         Wrapper for ``tao::window::Window::outer_size``.
         """
-        return await event_register("window.outer_size", {}, result_type=Dimensions)
+        return await event_register(
+            "window.outer_size", {}, result_type=Dimensions, label=self.label
+        )
 
     async def outer_position(self) -> MonitorPosition:
         """Return outer position.
@@ -399,5 +493,5 @@ class Window:
         :rtype: MonitorPosition
         """
         return await event_register(
-            "window.outer_position", {}, result_type=MonitorPosition
+            "window.outer_position", {}, result_type=MonitorPosition, label=self.label
         )
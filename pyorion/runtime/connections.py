@@ -2,34 +2,127 @@
 # SPDX-License-Identifier: Apache-2.0
 # SPDX-License-Identifier: MIT
 
-"""WebSocket server connections for the PyOrion runtime.
-
-This module provides the WebSocket server used for communication
-between the PyOrion backend and frontend clients. It manages
-client registration, message dispatching, and broadcasting responses.
+"""Frontend connections for the PyOrion runtime.
+
+Pages call ``invoke`` over wry's IPC channel. The event loop answers Rust
+APIs itself and forwards everything else as a ``webview.invoke`` event,
+which :func:`serve_webview_commands` runs as a registered ``@command`` and
+answers through ``webview.respond``.
+
+Pages using ``PyOrionConnections`` talk to a WebSocket server. The
+embedded one of the WebFrame process forwards what it does not serve
+itself as ``websocket.message`` events, answered here through
+``websocket.respond``. The Python server below needs the optional
+``websockets`` package; it manages client registration, message
+dispatching, and broadcasting responses.
 """
 
+from __future__ import annotations
+
 import asyncio
 import json
 import logging
+from typing import TYPE_CHECKING
 from urllib.parse import urlparse
 
-import websockets
 from pydantic import BaseModel
-from websockets import ServerConnection
 
 from pyorion.pyinvoke import _event_callbacks, make_callback
 from pyorion.runtime import core
+from pyorion.runtime.runtime_handle import EventModel, event_register, subscribe_events
 from pyorion.utils import make_json_safe
 
+if TYPE_CHECKING:
+    from websockets import ServerConnection
+
+WEBVIEW_INVOKE_EVENT = "webview.invoke"
+"""Topic under which the event loop forwards page calls to Python."""
+
+WEBSOCKET_MESSAGE_EVENT = "websocket.message"
+"""Topic under which the embedded WebSocket server forwards messages."""
+
 
 def list_commands() -> dict[str, list[str]]:
     """Return a mapping of registered event names -> handler function names."""
     return {key: [f.__name__ for f in funcs] for key, funcs in _event_callbacks.items()}
 
 
+async def _answer_invoke(event: EventModel) -> None:
+    """Run the command a page invoked and send the answer back to it."""
+    payload = event.payload if isinstance(event.payload, dict) else {}
+    call_id = payload.get("id")
+    cmd = payload.get("cmd")
+    if not isinstance(call_id, int) or not isinstance(cmd, str):
+        logging.warning("Ignoring malformed invoke: %s", event.payload)
+        return
+
+    args = payload.get("args")
+    response = await make_callback(
+        cmd, call_id, call_id, args if isinstance(args, dict) else {}
+    )
+    error = response.get("error")
+    result = None if error is not None else make_json_safe(response.get("result"))
+    try:
+        await event_register(
+            "webview.respond", [call_id, result, error], label=event.window
+        )
+    except Exception as exc:
+        logging.error("Failed to answer invoke '%s' (%s): %s", cmd, call_id, exc)
+
+
+async def _answer_socket_message(event: EventModel) -> None:
+    """Run the command of an embedded WebSocket message and answer it."""
+    message = event.payload if isinstance(event.payload, dict) else {}
+    client = message.get("client")
+    cmd = message.get("cmd")
+    if not isinstance(client, int) or not isinstance(cmd, str):
+        logging.warning("Ignoring malformed socket message: %s", event.payload)
+        return
+
+    payload = message.get("payload")
+    response = await make_callback(
+        cmd,
+        message.get("result_id", 0),
+        message.get("error_id", 0),
+        payload if isinstance(payload, dict) else {},
+    )
+    try:
+        await event_register("websocket.respond", [client, make_json_safe(response)])
+    except Exception as exc:
+        logging.error("Failed to answer socket message '%s': %s", cmd, exc)
+
+
+async def _answer_frontend(event: EventModel) -> None:
+    """Route a forwarded page call by the topic it came in under."""
+    if event.event == WEBSOCKET_MESSAGE_EVENT:
+        await _answer_socket_message(event)
+    else:
+        await _answer_invoke(event)
+
+
+async def serve_webview_commands(retry_interval: float = 0.2) -> None:
+    """Answer the calls pages make to Python commands.
+
+    Subscribes to :data:`WEBVIEW_INVOKE_EVENT` and
+    :data:`WEBSOCKET_MESSAGE_EVENT` as soon as the IPC server of the
+    WebFrame process accepts connections.
+
+    :param retry_interval: Seconds between connection attempts.
+    :type retry_interval: float
+    """
+    topics = [WEBVIEW_INVOKE_EVENT, WEBSOCKET_MESSAGE_EVENT]
+    while True:
+        try:
+            await subscribe_events(topics, _answer_frontend)
+            return
+        except (ConnectionError, OSError):
+            await asyncio.sleep(retry_interval)
+
+
 async def handle_frontend_connections(websocket: ServerConnection) -> None:
     """Handle an individual frontend WebSocket connection."""
+    import websockets
+
     core.connected_clients.add(websocket)
     try:
         async for message in websocket:
@@ -89,7 +182,13 @@ async def handle_frontend_connections(websocket: ServerConnection) -> None:
 
 
 async def create_websocket_server(url: str) -> None:
-    """Create and run the frontend WebSocket server (with optional path)."""
+    """Create and run the frontend WebSocket server (with optional path).
+
+    Requires the optional ``websockets`` package; the embedded server of
+    the WebFrame process does not.
+    """
+    import websockets
+
     parsed = urlparse(url)
     host, port = parsed.hostname, parsed.port
     expected_path = parsed.path if parsed.path and parsed.path != "/" else None
@@ -11,23 +11,30 @@ parts of the runtime:
 * ``background_tasks`` — Set of tracked asyncio tasks to prevent
   premature garbage collection.
 * ``connected_clients`` — Active WebSocket client connections.
+* ``auth_token`` — Session token presented by IPC clients.
+* ``ipc_name`` — Endpoint name of the IPC server.
 
 These globals act as singletons for coordination between the Python
 frontend and the Rust-backed runtime.
 """
 
 import asyncio
-from typing import Any
-
-from websockets import ServerConnection
+from typing import TYPE_CHECKING, Any
 
 import multiprocessing
 
+if TYPE_CHECKING:
+    from websockets import ServerConnection
+
 
 
 
 
 close_signale = multiprocessing.Event()
+#: Session token the IPC server was launched with; set by the runtime.
+auth_token: str | None = None
+#: Endpoint name of the IPC server; unique per launch, set by the runtime.
+ipc_name: str = "pyframe_pipe"
 #: Central task queue for inter-coroutine communication.
 #: Each task is represented as a ``dict[str, Any]``.
 task_queue: asyncio.Queue[dict[str, Any]] = asyncio.Queue()
@@ -38,4 +45,4 @@ background_tasks: set[asyncio.Task] = set()
 
 #: Set of currently connected frontend WebSocket clients.
 #: Populated by :func:`handle_frontend_connections`.
-connected_clients: set[ServerConnection] = set()
+connected_clients: set["ServerConnection"] = set()
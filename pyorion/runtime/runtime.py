@@ -10,6 +10,8 @@ subprocesses, and WebSocket communication.
 """
 
 import asyncio
+import os
+import secrets
 from collections.abc import Coroutine
 from multiprocessing import get_context
 from multiprocessing.context import SpawnProcess
@@ -18,9 +20,9 @@ from pathlib import Path
 from pydantic import AnyHttpUrl
 
 from pyorion._pyorion import create_webframe
-from pyorion.setup.types import WebSocketConfig, WindowOptions
+from pyorion.setup.types import IpcServerConfig, WebSocketConfig, WindowOptions
 from . import core
-from .connections import create_websocket_server
+from .connections import create_websocket_server, serve_webview_commands
 from .runtime_handle import eventloop_sender
 
 
@@ -95,11 +97,15 @@ async def run_native_runtime(
     protocols: list[str] | None = None,
     auto_reconnect: bool = True,
     reconnect_interval: int = 3000,
+    server_cfg: IpcServerConfig | None = None,
+    ipc_name: str | None = None,
+    embedded_websocket: bool = False,
 ) -> None:
     """Start the native runtime environment.
 
     This function launches the native WebFrame subprocess and manages
-    background servers (WebSocket and event loop sender). It ensures that
+    background servers (WebSocket, event loop sender and the command loop
+    answering pages' ``invoke`` calls). It ensures that
     processes and tasks are started, monitored, and terminated gracefully.
 
     A ``multiprocessing.Manager`` is used to create a shared shutdown event.
@@ -119,25 +125,45 @@ async def run_native_runtime(
     :type auto_reconnect: bool, optional
     :param reconnect_interval: Interval in milliseconds before attempting reconnect.
     :type reconnect_interval: int, optional
+    :param server_cfg: Limits and session token of the IPC server, defaults
+                       (with a fresh token) when ``None``.
+    :type server_cfg: IpcServerConfig | None, optional
+    :param ipc_name: Name of the IPC endpoint. Defaults to a name unique to
+                     this launch, so several apps can run side by side.
+    :type ipc_name: str | None, optional
+    :param embedded_websocket: Serve the WebSocket from the WebFrame process
+                               on a random loopback port instead of a Python
+                               server; ``websocket_url`` then only sets the
+                               path and may be omitted. Needs no
+                               ``websockets`` package.
+    :type embedded_websocket: bool, optional
     :return: None
     :rtype: None
     """
     socket_cfg = None
 
     loop = asyncio.get_running_loop()
-    if internal_proto and websocket_url is not None:
+    if internal_proto and (websocket_url is not None or embedded_websocket):
         socket_cfg = WebSocketConfig(
-            url=websocket_url,
+            url=str(websocket_url or ""),
             protocols=protocols,
             auto_reconnect=auto_reconnect,
             reconnect_interval=reconnect_interval,
+            embedded=embedded_websocket,
         )
-        launch_background_task(create_websocket_server(str(websocket_url)))
+        if not embedded_websocket:
+            launch_background_task(create_websocket_server(str(websocket_url)))
 
     launch_background_task(eventloop_sender())
+    launch_background_task(serve_webview_commands())
     socket_cfg_json = (
         socket_cfg.model_dump_json(by_alias=True) if socket_cfg is not None else None
     )
+    # the token reaches the child through the spawn pipe, never argv or env
+    server_cfg = server_cfg if server_cfg is not None else IpcServerConfig()
+    core.auth_token = server_cfg.auth_token
+    core.ipc_name = ipc_name or f"pyorion-{os.getpid()}-{secrets.token_hex(4)}"
+    server_cfg_json = server_cfg.model_dump_json(by_alias=True)
     ctx = get_context("spawn")
     with ctx.Manager() as manager:
         close_event = manager.Event()
@@ -147,7 +173,13 @@ async def run_native_runtime(
         core.close_signale = shutdown_event = close_event
         proc = ctx.Process(
             target=create_webframe,
-            args=(config, socket_cfg_json, "pyframe_pipe", shutdown_event),
+            args=(
+                config,
+                socket_cfg_json,
+                core.ipc_name,
+                shutdown_event,
+                server_cfg_json,
+            ),
             daemon=False,
         )
         proc.start()
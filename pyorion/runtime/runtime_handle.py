@@ -10,36 +10,52 @@ receive messages between Python and the Rust backend.
 """
 
 import asyncio
+import inspect
 import json
 import traceback
-from typing import Any, Callable, Optional, TypeVar, cast
+from typing import Any, AsyncIterator, Awaitable, Callable, Optional, TypeVar, cast
 
 from pydantic import BaseModel
 
-from pyorion._pyorion import send_event_over_platform
+from pyorion._pyorion import IpcClient
 from pyorion.utils import make_json_safe, normalize_args
+from . import core
 
 
 R = TypeVar("R", bound=BaseModel)
 T = TypeVar("T")
 
+PROTOCOL_VERSION = 1
+"""Wire protocol this package speaks; a ``_pyorion`` build speaking another
+one refuses the connection with a :class:`ConnectionError` naming both."""
+
+CHUNK_CODE = 100
+"""Code of a frame carrying one chunk of a streamed result."""
+
 
 class ApiRequestModel(BaseModel):
     """Representation of a request sent to the Rust event loop."""
 
     id: int
     method: str
-    args: list[Any]
+    args: list[Any] | dict[str, Any]
+    label: str | None = None
 
     def to_json_array(self) -> list[Any]:
         """Convert the request into a JSON-safe array format.
 
-        The `args` list is normalized via :func:`make_json_safe`.
+        The `args` list or keyword mapping is normalized via
+        :func:`make_json_safe`. The target window label is only appended
+        when set.
 
-        :return: List representation ``[id, method, args]`` suitable for JSON.
+        :return: List representation ``[id, method, args, label?]``
+                 suitable for JSON.
         :rtype: list[Any]
         """
-        return [self.id, self.method, [make_json_safe(a) for a in self.args]]
+        arr = [self.id, self.method, make_json_safe(self.args)]
+        if self.label is not None:
+            arr.append(self.label)
+        return arr
 
 
 class ApiResponseModel(BaseModel):
@@ -66,26 +82,145 @@ class ApiResponseModel(BaseModel):
 
 
 class ApiError(Exception):
-    """Error raised when the Rust event loop returns a non-zero status code."""
+    """Error raised when the Rust event loop returns a non-zero status code.
+
+    ``code`` is one of the stable codes below and ``details`` carries
+    machine-readable information, at least its ``kind``. Each code maps to a
+    dedicated subclass, so callers can catch e.g. :class:`WindowNotFoundError`
+    instead of matching on ``msg``.
+    """
 
-    def __init__(self, code: int, msg: str) -> None:
+    INTERNAL = 1000
+    UNKNOWN_METHOD = 1001
+    INVALID_ARGUMENTS = 1002
+    WINDOW_NOT_FOUND = 1003
+    PERMISSION_DENIED = 1004
+    IO = 1005
+    PLATFORM_UNSUPPORTED = 1006
+    TIMEOUT = 1007
+    CANCELLED = 1008
+    DUPLICATE_REQUEST = 1009
+    PROTOCOL = 1010
+
+    def __init__(
+        self, code: int, msg: str, details: dict[str, Any] | None = None
+    ) -> None:
         """Initialize the API error.
 
         :param code: Numeric error code returned by Rust.
         :type code: int
         :param msg: Human-readable error message.
         :type msg: str
+        :param details: Machine-readable error details.
+        :type details: dict[str, Any] | None
         """
         super().__init__(f"[API-{code}] {msg}")
         self.code = code
         self.msg = msg
+        self.details: dict[str, Any] = details or {}
+
+    @property
+    def kind(self) -> str | None:
+        """Short name of the error class, e.g. ``"invalidArguments"``."""
+        return self.details.get("kind")
+
+    @classmethod
+    def from_response(cls, resp: "ApiResponseModel") -> "ApiError":
+        """Build the matching :class:`ApiError` subclass for a failed response.
+
+        :param resp: Response with a non-zero code.
+        :type resp: ApiResponseModel
+        :return: The typed error.
+        :rtype: ApiError
+        """
+        details = resp.result if isinstance(resp.result, dict) else None
+        error_cls = _ERROR_CLASSES.get(resp.code, ApiError)
+        return error_cls(resp.code, resp.msg, details)
+
+
+class InternalError(ApiError):
+    """The backend failed unexpectedly."""
+
+
+class UnknownMethodError(ApiError):
+    """The called method does not exist; ``details["method"]`` names it."""
+
+
+class InvalidArgumentsError(ApiError):
+    """Arguments could not be deserialized.
+
+    ``details["argument"]`` and ``details["index"]`` name the failing
+    parameter when known.
+    """
+
+
+class WindowNotFoundError(ApiError):
+    """No window has the requested ``details["label"]``."""
+
+
+class PermissionDeniedError(ApiError):
+    """The operating system denied the operation."""
+
+
+class ApiIOError(ApiError):
+    """An I/O operation failed."""
+
+
+class PlatformUnsupportedError(ApiError):
+    """The operation is not supported on this platform."""
+
+
+class ApiTimeoutError(ApiError):
+    """The request exceeded its deadline; ``details["timeoutMs"]``."""
+
+
+class RequestCancelledError(ApiError):
+    """The request was cancelled before it completed."""
+
+
+class DuplicateRequestError(ApiError):
+    """A request with the same id is already in flight."""
+
+
+class ProtocolError(ApiError):
+    """The frame was too large, not UTF-8, not JSON or not a request, or the
+    peer speaks another protocol version."""
+
+
+_ERROR_CLASSES: dict[int, type[ApiError]] = {
+    ApiError.INTERNAL: InternalError,
+    ApiError.UNKNOWN_METHOD: UnknownMethodError,
+    ApiError.INVALID_ARGUMENTS: InvalidArgumentsError,
+    ApiError.WINDOW_NOT_FOUND: WindowNotFoundError,
+    ApiError.PERMISSION_DENIED: PermissionDeniedError,
+    ApiError.IO: ApiIOError,
+    ApiError.PLATFORM_UNSUPPORTED: PlatformUnsupportedError,
+    ApiError.TIMEOUT: ApiTimeoutError,
+    ApiError.CANCELLED: RequestCancelledError,
+    ApiError.DUPLICATE_REQUEST: DuplicateRequestError,
+    ApiError.PROTOCOL: ProtocolError,
+}
+
+
+class EventModel(BaseModel):
+    """Event pushed from the Rust event loop to subscribed clients."""
+
+    event: str
+    window: str | None = None
+    payload: Any = None
+
+
+EventHandler = Callable[[EventModel], Awaitable[None] | None]
 
 
 class PendingRegistry:
     """Registry for managing in-flight requests and their associated futures."""
 
-    def __init__(self, max_id: int = 255) -> None:
-        """Initialize the registry with a maximum ID limit."""
+    def __init__(self, max_id: int = 2**64 - 1) -> None:
+        """Initialize the registry with a maximum ID limit.
+
+        IDs are 64-bit on the wire, so wrap-around is practically never hit.
+        """
         self._pending: dict[int, asyncio.Future[Any]] = {}
         self._counter: int = 0
         self._max_id = max_id
@@ -135,24 +270,120 @@ class PendingRegistry:
 
 _pending = PendingRegistry()
 task_queue: asyncio.Queue[dict[str, Any]] = asyncio.Queue()
+_client: IpcClient | None = None
+
+
+def get_client() -> IpcClient:
+    """Return the process-wide persistent IPC client, creating it lazily."""
+    global _client
+    if _client is None:
+        _client = IpcClient(
+            core.ipc_name, token=core.auth_token, protocol=PROTOCOL_VERSION
+        )
+    return _client
+
+
+_event_handlers: dict[str, list[EventHandler]] = {}
+
+
+def _deliver_event(event: EventModel) -> None:
+    """Run every handler registered for the event's topic or ``"*"``."""
+    handlers = _event_handlers.get(event.event, []) + _event_handlers.get("*", [])
+    for handler in handlers:
+        try:
+            result = handler(event)
+            if inspect.isawaitable(result):
+                asyncio.ensure_future(result)
+        except Exception:
+            traceback.print_exc()
+
 
+async def subscribe_events(topics: list[str], handler: EventHandler) -> list[str]:
+    """Subscribe ``handler`` to event topics pushed by the Rust event loop.
 
-async def send_loop_event(data: list[Any]) -> list[Any] | None:
-    """Send an event to the Rust event loop over the platform bridge."""
+    Handlers run on the calling asyncio loop and may be coroutines.
+
+    :param topics: Topic names such as ``"window.resized"``, or ``"*"``.
+    :type topics: list[str]
+    :param handler: Callable receiving an :class:`EventModel`.
+    :type handler: EventHandler
+    :return: All topics this process is subscribed to.
+    :rtype: list[str]
+    """
+    loop = asyncio.get_running_loop()
+
+    def on_event(event: str, window: str | None, payload: Any) -> None:
+        # invoked from a Rust runtime thread
+        model = EventModel(event=event, window=window, payload=payload)
+        loop.call_soon_threadsafe(_deliver_event, model)
+
+    for topic in topics:
+        _event_handlers.setdefault(topic, []).append(handler)
     try:
-        response_str: Optional[Any] = await send_event_over_platform(
-            name="pyframe_pipe",
-            message=json.dumps(data),
+        resp = ApiResponseModel.from_array(
+            await get_client().subscribe(topics, on_event)
         )
+        if resp.code != 0:
+            raise ApiError.from_response(resp)
+    except BaseException:
+        # a failed subscription must not leave the handler behind
+        for topic in topics:
+            _event_handlers[topic].remove(handler)
+        raise
+    return cast(list[str], resp.result)
 
-        if response_str is None:
-            return None
-        return response_str
 
-    except Exception as exc:
-        print(f"Connection error: {exc}")
-        traceback.print_exc()
-        return None
+async def unsubscribe_events(
+    topics: list[str] | None = None, handler: EventHandler | None = None
+) -> list[str]:
+    """Remove event handlers and unsubscribe topics without handlers left.
+
+    :param topics: Topics to remove, ``None`` for all of them.
+    :type topics: list[str] | None
+    :param handler: Only remove this handler, ``None`` for every handler.
+    :type handler: EventHandler | None
+    :return: Topics this process is still subscribed to.
+    :rtype: list[str]
+    """
+    names = list(_event_handlers) if topics is None else topics
+    released = []
+    for topic in names:
+        handlers = _event_handlers.get(topic, [])
+        if handler is not None and handler in handlers:
+            handlers.remove(handler)
+        elif handler is None:
+            handlers.clear()
+        if not handlers:
+            _event_handlers.pop(topic, None)
+            released.append(topic)
+    if not released:
+        return sorted(_event_handlers)
+    resp = ApiResponseModel.from_array(await get_client().unsubscribe(released))
+    if resp.code != 0:
+        raise ApiError.from_response(resp)
+    return cast(list[str], resp.result)
+
+
+async def send_loop_event(data: list[Any], timeout: float | None = None) -> list[Any]:
+    """Send an event to the Rust event loop over the persistent client.
+
+    The client assigns its own wire id, so the response id is mapped back
+    to the id of ``data`` before it is returned. ``timeout`` (seconds) is
+    enforced by the server, which answers with a timeout error.
+
+    :raises ConnectionError: When the server cannot be reached, refuses the
+        connection (e.g. a ``_pyorion`` of another protocol version) or the
+        connection drops before the response.
+    :raises TimeoutError: When the server does not answer within ``timeout``.
+    """
+    label = data[3] if len(data) > 3 else None
+    response: Optional[Any] = await get_client().call(
+        data[1], json.dumps(data[2]), timeout=timeout, label=label
+    )
+    if response is None:
+        raise ConnectionError(f"No response to {data[1]}")
+    response[0] = data[0]
+    return cast(list[Any], response)
 
 
 async def handle_event_loop_response(
@@ -162,19 +393,44 @@ async def handle_event_loop_response(
     resp = ApiResponseModel.from_array(arr)
     if future:
         if resp.code != 0:
-            future.set_exception(ApiError(resp.code, resp.msg))
+            future.set_exception(ApiError.from_response(resp))
         else:
             future.set_result(resp.result)
     else:
         _pending.resolve(
             resp.id,
-            error=ApiError(resp.code, resp.msg) if resp.code != 0 else None,
+            error=ApiError.from_response(resp) if resp.code != 0 else None,
             result=None if resp.code != 0 else resp.result,
         )
 
 
+async def _dispatch(
+    data: list[Any],
+    future: asyncio.Future[Any] | None,
+    timeout: float | None = None,
+) -> None:
+    """Send a single task and resolve its future with the response.
+
+    A failed send fails the future, so callers see the connection error
+    instead of waiting for a response that never comes.
+    """
+    try:
+        arr = await send_loop_event(data, timeout)
+        await handle_event_loop_response(arr, future=future)
+    except Exception as exc:
+        if future is None:
+            _pending.resolve(data[0], error=exc)
+        elif not future.done():
+            future.set_exception(exc)
+
+
 async def eventloop_sender() -> None:
-    """Continuous dispatcher loop for sending tasks to the Rust event loop."""
+    """Continuous dispatcher loop for sending tasks to the Rust event loop.
+
+    Tasks are dispatched concurrently; the persistent client multiplexes
+    them over its connections and matches responses by id.
+    """
+    in_flight: set[asyncio.Task[None]] = set()
     try:
         while True:
             task = await task_queue.get()
@@ -182,20 +438,17 @@ async def eventloop_sender() -> None:
             data: list[Any] | None = task.get("data")
             if data is None:
                 return
-            try:
-                arr = await send_loop_event(data)
-                if arr:
-                    await handle_event_loop_response(arr, future=future)
-            except Exception as exc:
-                if future and not future.done():
-                    future.set_exception(exc)
-            finally:
-                await asyncio.sleep(0.01)
+            timeout: float | None = task.get("timeout")
+            dispatch = asyncio.create_task(_dispatch(data, future, timeout))
+            in_flight.add(dispatch)
+            dispatch.add_done_callback(in_flight.discard)
 
     except asyncio.CancelledError as exc:
         print(f"ui_endless_event_loop_tasks() cancelled: {exc}")
     finally:
         print("gui_endless_event_loop_tasks() terminated.")
+        for dispatch in in_flight:
+            dispatch.cancel()
         _pending.cancel_all(RuntimeError("Event loop terminated"))
 
 
@@ -209,6 +462,8 @@ async def event_register(
     method: str,
     args: Any | None = None,
     result_type: type[R] | Callable[[Any], T] = _identity,
+    label: str | None = None,
+    timeout: float | None = 10.0,
 ) -> R | T:
     """Send a typed request to the Rust event loop and wait for a typed response.
 
@@ -223,27 +478,40 @@ async def event_register(
     :param method: Name of the Rust API method to call.
     :type method: str
     :param args: Optional arguments for the method, normalized automatically.
+                 A ``dict`` passes them by name instead of by position.
     :type args: Any | None
     :param result_type: Expected result type. Can be:
                         * A subclass of :class:`pydantic.BaseModel` (validated).
                         * A callable for custom transformations.
                         * Any type for raw passthrough.
     :type result_type: type[BaseModel] | Callable | Any
+    :param label: Label of the target window, ``None`` for the main window.
+    :type label: str | None
+    :param timeout: Deadline in seconds, enforced by the server. ``None``
+                    waits indefinitely.
+    :type timeout: float | None
     :return: Parsed and typed response.
     :rtype: R | T
-    :raises ApiError: If the Rust backend returned an error code.
+    :raises ApiError: If the Rust backend returned an error code, as the
+                      matching subclass such as :class:`ApiTimeoutError`.
     :raises asyncio.TimeoutError: If no response is received in time.
     :raises Exception: For connection or serialization errors.
     """
     req_id = _pending.next_id()
-    request = ApiRequestModel(id=req_id, method=method, args=normalize_args(args))
+    request = ApiRequestModel(
+        id=req_id, method=method, args=normalize_args(args), label=label
+    )
     future: asyncio.Future[Any] = asyncio.get_event_loop().create_future()
     _pending.register(req_id, future)
 
-    await task_queue.put({"data": request.to_json_array(), "future": future})
+    await task_queue.put(
+        {"data": request.to_json_array(), "future": future, "timeout": timeout}
+    )
 
     try:
-        raw_result = await asyncio.wait_for(future, timeout=10.0)
+        # the server reports timeouts; the margin only covers a dead server
+        local_timeout = None if timeout is None else timeout + 2.0
+        raw_result = await asyncio.wait_for(future, timeout=local_timeout)
 
         if isinstance(result_type, type) and issubclass(result_type, BaseModel):
             if hasattr(result_type, "model_validate"):  # Pydantic v2
@@ -260,3 +528,38 @@ async def event_register(
         raise
     finally:
         _pending.pop(req_id, None)
+
+
+async def event_stream(
+    method: str,
+    args: Any | None = None,
+    label: str | None = None,
+    timeout: float | None = 10.0,
+) -> AsyncIterator[Any]:
+    """Call a streaming Rust handler and iterate over the chunks it sends.
+
+    Leaving the iteration early cancels the request, so the handler stops
+    at its next chunk.
+
+    :param method: Name of the Rust API method to call.
+    :type method: str
+    :param args: Optional arguments for the method, normalized automatically.
+    :type args: Any | None
+    :param label: Label of the target window, ``None`` for the main window.
+    :type label: str | None
+    :param timeout: Longest wait in seconds for the next chunk, enforced by
+                    the server. ``None`` waits indefinitely.
+    :type timeout: float | None
+    :return: Async iterator over the chunks.
+    :rtype: AsyncIterator[Any]
+    :raises ApiError: If the handler failed, after the chunks it sent before.
+    """
+    stream = await get_client().stream(
+        method, json.dumps(normalize_args(args)), timeout=timeout, label=label
+    )
+    async for frame in stream:
+        resp = ApiResponseModel.from_array(frame)
+        if resp.code == CHUNK_CODE:
+            yield resp.result
+        elif resp.code != 0:
+            raise ApiError.from_response(resp)
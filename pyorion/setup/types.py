@@ -7,6 +7,7 @@
 from __future__ import annotations
 
 import base64
+import secrets
 from enum import Enum
 from pathlib import Path
 from typing import Any, ClassVar
@@ -36,13 +37,17 @@ class WebSocketConfig(BaseSchema):
     """WebSocketConfig - Configuration model for PyOrionConnections.
 
     Fields:
-    - url (str): WebSocket URL (must start with ws:// or wss://)
+    - url (str): WebSocket URL (must start with ws:// or wss://); only its
+      path is used when ``embedded``
     - protocols (list[str], optional): Subprotocols for the handshake
     - auto_reconnect (bool, optional): Enable automatic reconnect
     - reconnect_interval (int, optional): Reconnect delay in milliseconds
+    - embedded (bool): Serve the socket from the WebFrame process on a
+      random loopback port instead of a Python server
     """
 
     url: str = Field(
+        default="",
         description="The WebSocket URL (e.g., ws://localhost:8765 or wss://example.com/socket).",
     )
     protocols: list[str] | None = Field(
@@ -54,6 +59,10 @@ class WebSocketConfig(BaseSchema):
     reconnect_interval: int | None = Field(
         default=3000, description="Reconnect interval in milliseconds."
     )
+    embedded: bool = Field(
+        default=False,
+        description="Serve the socket from the WebFrame process on a random port.",
+    )
 
     class Config:
         """Validate example configuration"""
@@ -69,6 +78,72 @@ class WebSocketConfig(BaseSchema):
         }
 
 
+class TlsConfig(BaseSchema):
+    """TlsConfig - TLS settings of the TCP listener.
+
+    Fields:
+    - cert_path (Path): PEM file with the server certificate chain
+    - key_path (Path): PEM file with the server's private key
+    - client_ca_path (Path, optional): CA bundle client certificates must
+      chain to; clients without one are refused when set
+    """
+
+    cert_path: Path = Field(description="PEM file with the certificate chain.")
+    key_path: Path = Field(description="PEM file with the private key.")
+    client_ca_path: Path | None = Field(
+        default=None, description="PEM file with the CAs for client certificates."
+    )
+
+
+class TcpServerConfig(BaseSchema):
+    """TcpServerConfig - Additional TCP listener of the IPC server.
+
+    Fields:
+    - address (str): Address to listen on, e.g. ``127.0.0.1:7878``
+    - tls (TlsConfig, optional): Serve TLS instead of plain TCP
+    """
+
+    address: str = Field(description="Address to listen on, e.g. 127.0.0.1:7878.")
+    tls: TlsConfig | None = Field(
+        default=None, description="Serve TLS instead of plain TCP."
+    )
+
+
+class IpcServerConfig(BaseSchema):
+    """IpcServerConfig - Limits of the frame process' IPC server.
+
+    Fields:
+    - max_frame_size (int): Largest accepted request frame in bytes
+    - max_violations (int): Malformed frames tolerated before disconnecting
+    - auth_token (str): Session secret every connection has to present
+    - allowed_pids (list[int]): Processes allowed to connect, empty for any
+    - tcp (TcpServerConfig, optional): Also listen on TCP
+    """
+
+    max_frame_size: int = Field(
+        default=16 * 1024 * 1024,
+        gt=0,
+        description="Largest accepted request frame in bytes.",
+    )
+    max_violations: int = Field(
+        default=3,
+        ge=0,
+        description="Malformed frames a connection may send before it is closed.",
+    )
+    auth_token: str = Field(
+        default_factory=lambda: secrets.token_urlsafe(32),
+        repr=False,
+        description="Session secret; a fresh one is generated per launch.",
+    )
+    allowed_pids: list[int] = Field(
+        default_factory=list,
+        description="Process ids allowed to connect. Empty allows any process.",
+    )
+    tcp: TcpServerConfig | None = Field(
+        default=None, description="Also listen on TCP, e.g. for remote automation."
+    )
+
+
 """ # === Example usage ===
 if __name__ == "__main__":
     # Validate example configuration
@@ -260,6 +335,17 @@ class Monitor(BaseSchema):
     video_modes: list[MonitorVideoMode]
 
 
+class SymlinkPolicy(str, Enum):
+    """Umgang des ``wry://``-Protokolls mit Symlinks im Asset-Ordner."""
+
+    #: Nur Symlinks, deren Ziel im Ordner bleibt
+    contained = "contained"
+    #: Jedem Symlink folgen, auch aus dem Ordner hinaus
+    follow = "follow"
+    #: Jeden Pfad über einen Symlink ablehnen
+    deny = "deny"
+
+
 class WebViewOptions(BaseSchema):
     """Optionen zur Konfiguration eines WebViews."""
 
@@ -282,6 +368,7 @@ class WebViewOptions(BaseSchema):
     zoom_hotkeys: bool | None = None
     background_throttling: bool | None = None
     back_forward_navigation_gestures: bool | None = None
+    asset_symlinks: SymlinkPolicy | None = None
 
 
 class WindowOptions(BaseSchema):
@@ -113,16 +113,21 @@ def make_json_safe(obj: Any) -> Any:
     return str(obj)
 
 
-def normalize_args(args: Any | None) -> list[Any]:
-    """Normalize arguments to a JSON-safe list.
+def normalize_args(args: Any | None) -> list[Any] | dict[str, Any]:
+    """Normalize arguments to a JSON-safe list or keyword mapping.
+
+    A ``dict`` is kept as keyword arguments, keyed by the Rust parameter
+    names; anything else becomes a positional list.
 
     :param args: The arguments to normalize.
     :type args: Any | None
-    :return: List of JSON-safe arguments.
-    :rtype: list[Any]
+    :return: JSON-safe positional or keyword arguments.
+    :rtype: list[Any] | dict[str, Any]
     """
     if args is None:
         return []
+    if isinstance(args, dict):
+        return {str(k): make_json_safe(v) for k, v in args.items()}
     if isinstance(args, list):
         return [make_json_safe(a) for a in args]
     return [make_json_safe(args)]
@@ -5,21 +5,22 @@
 use anyhow::Result;
 use pyorion_macros::api;
 
-use crate::api_manager::ApiManager;
+use crate::api_manager::{ApiManager, ApiRequest, AppHandle};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use tao::window::Window;
 
 pub fn dialog_api(_api_manager: &mut ApiManager) {
-    _api_manager.register_api("dialog.showMessage", show_message);
-    _api_manager.register_api("dialog.pickFile", pick_file);
-    _api_manager.register_api("dialog.pickFiles", pick_files);
-    _api_manager.register_api("dialog.pickDir", pick_dir);
-    _api_manager.register_api("dialog.pickDirs", pick_dirs);
-    _api_manager.register_api("dialog.saveFile", save_file);
+    _api_manager.register_async_api("dialog.showMessage", show_message);
+    _api_manager.register_async_api("dialog.pickFile", pick_file);
+    _api_manager.register_async_api("dialog.pickFiles", pick_files);
+    _api_manager.register_async_api("dialog.pickDir", pick_dir);
+    _api_manager.register_async_api("dialog.pickDirs", pick_dirs);
+    _api_manager.register_async_api("dialog.saveFile", save_file);
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 enum MessageLevel {
     #[serde(rename = "info")]
     Info,
@@ -29,22 +30,35 @@ enum MessageLevel {
     Error,
 }
 
-#[api]
-fn show_message(title: String, content: Option<String>, level: Option<MessageLevel>) -> Result<()> {
-    let parent = app.app_context()?.get_window()?;
+// Dialoge laufen asynchron: nur das Erzeugen mit dem Elternfenster passiert
+// auf dem Main-Thread, das Warten auf die Auswahl blockiert die UI nicht.
+
+#[api(async)]
+async fn show_message(
+    title: String,
+    content: Option<String>,
+    level: Option<MessageLevel>,
+) -> Result<()> {
+    let label = req.label().map(str::to_string);
     let content = content.unwrap_or_default();
     let level = level.unwrap_or(MessageLevel::Info);
 
-    rfd::MessageDialog::new()
-        .set_title(&title)
-        .set_description(&content)
-        .set_parent(&parent)
-        .set_level(match level {
-            MessageLevel::Info => rfd::MessageLevel::Info,
-            MessageLevel::Warning => rfd::MessageLevel::Warning,
-            MessageLevel::Error => rfd::MessageLevel::Error,
+    let dialog = app
+        .run_on_main(move |app, _, _| {
+            let parent = app.app_context()?.get_window(label.as_deref())?;
+            Ok(rfd::AsyncMessageDialog::new()
+                .set_title(&title)
+                .set_description(&content)
+                .set_parent(&*parent)
+                .set_level(match level {
+                    MessageLevel::Info => rfd::MessageLevel::Info,
+                    MessageLevel::Warning => rfd::MessageLevel::Warning,
+                    MessageLevel::Error => rfd::MessageLevel::Error,
+                })
+                .show())
         })
-        .show();
+        .await?;
+    dialog.await;
 
     Ok(())
 }
@@ -53,8 +67,8 @@ fn _create_dialog(
     parent: &Window,
     filters: Option<Vec<String>>,
     start_dir: Option<String>,
-) -> rfd::FileDialog {
-    let mut dialog = rfd::FileDialog::new();
+) -> rfd::AsyncFileDialog {
+    let mut dialog = rfd::AsyncFileDialog::new();
     if let Some(extensions) = filters {
         if !extensions.is_empty() {
             let extensions = extensions.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
@@ -67,59 +81,75 @@ fn _create_dialog(
     dialog.set_parent(parent)
 }
 
-// In allen relevanten Funktionen (ohne & vor parent):
+/// Erzeugt den Dialog für das Fenster der Anfrage auf dem Main-Thread.
+fn _open_dialog<T, F>(
+    app: &AppHandle,
+    req: &ApiRequest,
+    filters: Option<Vec<String>>,
+    start_dir: Option<String>,
+    open: F,
+) -> impl std::future::Future<Output = Result<T>> + Send + 'static
+where
+    T: Send + 'static,
+    F: FnOnce(rfd::AsyncFileDialog) -> T + Send + 'static,
+{
+    let label = req.label().map(str::to_string);
+    app.run_on_main(move |app, _, _| {
+        let parent = app.app_context()?.get_window(label.as_deref())?;
+        Ok(open(_create_dialog(&parent, filters, start_dir)))
+    })
+}
+
+fn _path(file: rfd::FileHandle) -> std::path::PathBuf {
+    file.path().to_path_buf()
+}
 
-#[api]
-fn pick_file(filters: Option<Vec<String>>, start_dir: Option<String>) -> Result<Value> {
-    let parent = app.app_context()?.get_window()?;
-    let dialog = _create_dialog(&parent, filters, start_dir);
+#[api(async)]
+async fn pick_file(filters: Option<Vec<String>>, start_dir: Option<String>) -> Result<Value> {
+    let dialog = _open_dialog(&app, &req, filters, start_dir, |d| d.pick_file()).await?;
 
-    match dialog.pick_file() {
-        Some(file) => Ok(json!(file)),
+    match dialog.await {
+        Some(file) => Ok(json!(_path(file))),
         None => Ok(json!(null)),
     }
 }
 
-#[api]
-fn pick_files(filters: Option<Vec<String>>, start_dir: Option<String>) -> Result<Value> {
-    let parent = app.app_context()?.get_window()?;
-    let dialog = _create_dialog(&parent, filters, start_dir);
+#[api(async)]
+async fn pick_files(filters: Option<Vec<String>>, start_dir: Option<String>) -> Result<Value> {
+    let dialog = _open_dialog(&app, &req, filters, start_dir, |d| d.pick_files()).await?;
 
-    match dialog.pick_files() {
-        Some(files) => Ok(json!(files)),
+    match dialog.await {
+        Some(files) => Ok(json!(files.into_iter().map(_path).collect::<Vec<_>>())),
         None => Ok(json!(null)),
     }
 }
 
-#[api]
-fn pick_dir(start_dir: Option<String>) -> Result<Value> {
-    let parent = app.app_context()?.get_window()?;
-    let dialog = _create_dialog(&parent, None, start_dir);
+#[api(async)]
+async fn pick_dir(start_dir: Option<String>) -> Result<Value> {
+    let dialog = _open_dialog(&app, &req, None, start_dir, |d| d.pick_folder()).await?;
 
-    match dialog.pick_folder() {
-        Some(dir) => Ok(json!(dir)),
+    match dialog.await {
+        Some(dir) => Ok(json!(_path(dir))),
         None => Ok(json!(null)),
     }
 }
 
-#[api]
-fn pick_dirs(start_dir: Option<String>) -> Result<Value> {
-    let parent = app.app_context()?.get_window()?;
-    let dialog = _create_dialog(&parent, None, start_dir);
+#[api(async)]
+async fn pick_dirs(start_dir: Option<String>) -> Result<Value> {
+    let dialog = _open_dialog(&app, &req, None, start_dir, |d| d.pick_folders()).await?;
 
-    match dialog.pick_folders() {
-        Some(dirs) => Ok(json!(dirs)),
+    match dialog.await {
+        Some(dirs) => Ok(json!(dirs.into_iter().map(_path).collect::<Vec<_>>())),
         None => Ok(json!(null)),
     }
 }
 
-#[api]
-fn save_file(filters: Option<Vec<String>>, start_dir: Option<String>) -> Result<Value> {
-    let parent = app.app_context()?.get_window()?;
-    let dialog = _create_dialog(&parent, filters, start_dir);
+#[api(async)]
+async fn save_file(filters: Option<Vec<String>>, start_dir: Option<String>) -> Result<Value> {
+    let dialog = _open_dialog(&app, &req, filters, start_dir, |d| d.save_file()).await?;
 
-    match dialog.save_file() {
-        Some(file) => Ok(json!(file)),
+    match dialog.await {
+        Some(file) => Ok(json!(_path(file))),
         None => Ok(json!(null)),
     }
 }
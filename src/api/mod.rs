@@ -9,6 +9,7 @@ mod dialog;
 mod dirs;
 mod resource;
 mod vibrancy;
+mod websocket;
 mod webview;
 mod window;
 
@@ -20,4 +21,5 @@ pub fn register_api_instances(api_manager: &mut ApiManager) {
     clipboard::clipboard_api(api_manager);
     dirs::dirs_api(api_manager);
     resource::resource_api(api_manager);
+    websocket::websocket_api(api_manager);
 }
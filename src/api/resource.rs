@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::api_manager::ApiManager;
+use crate::api_manager::{blocking, ApiManager, ChunkSink};
+use crate::binary::Bytes;
+use crate::connections::framing::DEFAULT_MAX_FRAME_SIZE;
+use crate::error::ApiError;
 use anyhow::Result;
 // use base64::engine::general_purpose::STANDARD;
 // use base64::Engine;
@@ -10,10 +13,12 @@ use anyhow::Result;
 use mime_guess::from_path;
 //  use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use pyorion_macros::api;
+use schemars::JsonSchema;
 use serde::Deserialize;
-//  use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256};
 // use std::collections::HashMap;
 use std::fs;
+use std::io::Read;
 // use std::io::Cursor;
 // use std::sync::mpsc::channel;
 // use std::sync::mpsc::RecvTimeoutError;
@@ -22,27 +27,44 @@ use std::fs;
 use walkdir::WalkDir;
 
 /// Registrierung aller verfügbaren Ressourcen-APIs
+///
+/// Alle Dateisystem-Zugriffe laufen asynchron, damit große Verzeichnisse
+/// das Fenster nicht blockieren.
 pub fn resource_api(_api_manager: &mut ApiManager) {
     // _api_manager.register_api("resource.watch", watch);
     // _api_manager.register_api("resource.exists", exists);
     // _api_manager.register_api("resource.read", read);
     // _api_manager.register_api("resource.extract", extract);
-    _api_manager.register_api("resource.metadata", metadata);
-    _api_manager.register_api("resource.list", list);
-    _api_manager.register_api("resource.list_recursive", list_recursive);
-    _api_manager.register_api("resource.delete", delete);
-    _api_manager.register_api("resource.copy", copy);
+    _api_manager.register_async_api("resource.metadata", metadata);
+    _api_manager.register_async_api("resource.list", list);
+    _api_manager.register_async_api("resource.list_recursive", list_recursive);
+    _api_manager.register_async_api("resource.list_recursive_stream", list_recursive_stream);
+    _api_manager.register_async_api("resource.read_stream", read_stream);
+    _api_manager.register_async_api("resource.search", search);
+    _api_manager.register_async_api("resource.search_stream", search_stream);
+    _api_manager.register_async_api("resource.delete", delete);
+    _api_manager.register_async_api("resource.copy", copy);
     // _api_manager.register_api("resource.read_bytes", read_bytes);
     // _api_manager.register_api("resource.read_json", read_json);
     // _api_manager.register_api("resource.mime_type", mime_type);
-    // _api_manager.register_api("resource.hash", hash);
+    _api_manager.register_async_api("resource.hash", hash);
     // _api_manager.register_api("resource.translate", translate);
     // _api_manager.register_api("resource.bundle", bundle);
     // _api_manager.register_api("resource.thumbnail", thumbnail);
 }
 
+/// Pfade pro Block bei Verzeichnis-Durchläufen
+const PATHS_PER_CHUNK: usize = 256;
+
+/// Standard-Blockgröße beim gestreamten Lesen
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Größte Blockgröße beim gestreamten Lesen; ein Block passt auch
+/// base64-kodiert (JSON) samt Umschlag in einen Frame
+const MAX_READ_CHUNK_SIZE: usize = DEFAULT_MAX_FRAME_SIZE / 2;
+
 /// Unterstützte Kodierungsarten für das Lesen
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 enum EncodeType {
     #[serde(rename = "utf8")]
     Utf8,
@@ -112,54 +134,144 @@ fn extract(from: String, to: String) -> Result<()> {
 }
  */
 /// Gibt grundlegende Metadaten der Datei zurück
-#[api]
-fn metadata(path: String) -> Result<String> {
-    let metadata = fs::metadata(&path)?;
+#[api(async)]
+async fn metadata(path: String) -> Result<String> {
+    let meta = tokio::fs::metadata(&path).await?;
     let info = format!(
         "is_file: {}, is_dir: {}, len: {}",
-        metadata.is_file(),
-        metadata.is_dir(),
-        metadata.len()
+        meta.is_file(),
+        meta.is_dir(),
+        meta.len()
     );
     Ok(info)
 }
 
 /// Listet alle Einträge im angegebenen Verzeichnis (nicht rekursiv)
-#[api]
-fn list(dir: String) -> Result<Vec<String>> {
-    let entries = fs::read_dir(&dir)?
-        .map(|entry| entry.map(|e| e.file_name().into_string().unwrap_or_default()))
-        .collect::<std::io::Result<Vec<_>>>()?;
-    Ok(entries)
+#[api(async)]
+async fn list(dir: String) -> Result<Vec<String>> {
+    blocking(move || {
+        let entries = fs::read_dir(&dir)?
+            .map(|entry| entry.map(|e| e.file_name().into_string().unwrap_or_default()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        Ok(entries)
+    })
+    .await
 }
 
 /// Listet rekursiv alle Dateien im Verzeichnis
-#[api]
-fn list_recursive(path: String) -> Result<Vec<String>> {
-    let entries = WalkDir::new(&path)
+#[api(async)]
+async fn list_recursive(path: String) -> Result<Vec<String>> {
+    blocking(move || Ok(walk_files(&path).collect())).await
+}
+
+/// Wie `list_recursive`, aber gestreamt in Blöcken von Pfaden; das
+/// Ergebnis ist ihre Gesamtzahl
+#[api(stream)]
+async fn list_recursive_stream(path: String) -> Result<usize> {
+    blocking(move || send_paths(&chunks, walk_files(&path))).await
+}
+
+/// Liest eine Datei blockweise, gestreamt als Bytes; das Ergebnis ist die
+/// Gesamtgröße. `chunk_size` darf höchstens `MAX_READ_CHUNK_SIZE` sein
+#[api(stream)]
+async fn read_stream(path: String, chunk_size: Option<usize>) -> Result<u64> {
+    let chunk_size = chunk_size.unwrap_or(READ_CHUNK_SIZE).max(1);
+    if chunk_size > MAX_READ_CHUNK_SIZE {
+        return Err(ApiError::invalid_arguments(format!(
+            "chunk_size must not exceed {} bytes",
+            MAX_READ_CHUNK_SIZE
+        ))
+        .into());
+    }
+    blocking(move || {
+        let mut file = fs::File::open(&path)?;
+        let mut buf = vec![0u8; chunk_size];
+        let mut total = 0u64;
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            total += n as u64;
+            chunks.blocking_send(Bytes(buf[..n].to_vec()))?;
+        }
+        Ok(total)
+    })
+    .await
+}
+
+/// Sucht rekursiv nach Dateien, deren Name `query` enthält (ohne Beachtung
+/// der Groß-/Kleinschreibung)
+#[api(async)]
+async fn search(path: String, query: String) -> Result<Vec<String>> {
+    blocking(move || Ok(search_files(&path, &query).collect())).await
+}
+
+/// Wie `search`, aber gestreamt in Blöcken von Pfaden; das Ergebnis ist
+/// die Anzahl der Treffer
+#[api(stream)]
+async fn search_stream(path: String, query: String) -> Result<usize> {
+    blocking(move || send_paths(&chunks, search_files(&path, &query))).await
+}
+
+/// Alle Dateien unterhalb von `path`
+fn walk_files(path: &str) -> impl Iterator<Item = String> {
+    WalkDir::new(path)
         .into_iter()
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
         .map(|e| e.path().display().to_string())
-        .collect();
-    Ok(entries)
+}
+
+/// Alle Dateien unterhalb von `path`, deren Name `query` enthält
+fn search_files(path: &str, query: &str) -> impl Iterator<Item = String> {
+    let query = query.to_lowercase();
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(move |e| {
+            e.file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(&query)
+        })
+        .map(|e| e.path().display().to_string())
+}
+
+/// Sendet Pfade blockweise und gibt ihre Anzahl zurück
+fn send_paths(chunks: &ChunkSink, paths: impl Iterator<Item = String>) -> Result<usize> {
+    let mut total = 0;
+    let mut block = Vec::with_capacity(PATHS_PER_CHUNK);
+    for path in paths {
+        block.push(path);
+        if block.len() == PATHS_PER_CHUNK {
+            total += block.len();
+            chunks.blocking_send(std::mem::take(&mut block))?;
+        }
+    }
+    if !block.is_empty() {
+        total += block.len();
+        chunks.blocking_send(block)?;
+    }
+    Ok(total)
 }
 
 /// Löscht eine Datei oder ein Verzeichnis rekursiv
-#[api]
-fn delete(path: String) -> Result<()> {
-    if fs::metadata(&path)?.is_dir() {
-        fs::remove_dir_all(path)?;
+#[api(async)]
+async fn delete(path: String) -> Result<()> {
+    if tokio::fs::metadata(&path).await?.is_dir() {
+        tokio::fs::remove_dir_all(path).await?;
     } else {
-        fs::remove_file(path)?;
+        tokio::fs::remove_file(path).await?;
     }
     Ok(())
 }
 
 /// Kopiert eine Datei von A nach B
-#[api]
-fn copy(from: String, to: String) -> Result<()> {
-    fs::copy(from, to)?;
+#[api(async)]
+async fn copy(from: String, to: String) -> Result<()> {
+    tokio::fs::copy(from, to).await?;
     Ok(())
 }
 
@@ -185,13 +297,22 @@ fn mime_type(path: String) -> Result<String> {
     Ok(mime.essence_str().to_string())
 }
 
-/*
 /// Berechnet den SHA-256-Hash einer Datei
-#[api]
-fn hash(path: String) -> Result<String> {
-    let content = app.resource().load(&path)?;
-    let mut hasher = Sha256::new();
-    hasher.update(&content);
-    let hash = hasher.finalize();
-    Ok(format!("{:x}", hash))
-} */
+#[api(async)]
+async fn hash(path: String) -> Result<String> {
+    blocking(move || {
+        // blockweise lesen, damit große Dateien nicht komplett im Speicher landen
+        let mut file = fs::File::open(&path)?;
+        let mut hasher = Sha256::new();
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+}
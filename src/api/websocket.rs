@@ -0,0 +1,20 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use anyhow::Result;
+use pyorion_macros::api;
+use serde_json::Value;
+
+use crate::api_manager::ApiManager;
+
+pub fn websocket_api(api_manager: &mut ApiManager) {
+    api_manager.register_api("websocket.respond", respond);
+}
+
+/// Sends Python's answer to a message of the embedded WebSocket server back
+/// to the page `client`. Returns `false` once that page is gone.
+#[api]
+fn respond(client: u64, message: Value) -> Result<bool> {
+    Ok(app.ws_clients().send(client, message.to_string()))
+}
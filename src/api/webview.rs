@@ -4,31 +4,139 @@
 
 use anyhow::Result;
 use pyorion_macros::api;
+use serde_json::Value;
+use std::{sync::Mutex, time::Duration};
 
-use crate::api_manager::ApiManager;
+use crate::{
+    api_manager::{ApiManager, ApiResponse},
+    binary::Payload,
+    error::{code, ApiError},
+};
+
+/// Wait for `webview.evalWithResult` when the caller sets no timeout.
+const EVAL_TIMEOUT_MS: u64 = 10_000;
 
 pub fn webview_api(api_manager: &mut ApiManager) {
     api_manager.register_api("webview.isDevtoolsOpen", is_devtools_open);
     api_manager.register_api("webview.openDevtools", open_devtools);
     api_manager.register_api("webview.closeDevtools", close_devtools);
+    api_manager.register_api("webview.respond", respond);
+    api_manager.register_api("webview.eval", eval);
+    api_manager.register_api("webview.emit", emit);
+    api_manager.register_api("webview.broadcast", broadcast);
+    api_manager.register_async_api("webview.evalWithResult", eval_with_result);
 }
 
 #[api]
 fn is_devtools_open() -> Result<bool> {
-    let webview = app.app_context()?.get_webview()?;
+    let webview = app.app_context()?.get_webview(req.label())?;
     Ok(webview.is_devtools_open())
 }
 
 #[api]
 fn open_devtools() -> Result<()> {
-    let webview = app.app_context()?.get_webview()?;
+    let webview = app.app_context()?.get_webview(req.label())?;
     webview.open_devtools();
     Ok(())
 }
 
 #[api]
 fn close_devtools() -> Result<()> {
-    let webview = app.app_context()?.get_webview()?;
+    let webview = app.app_context()?.get_webview(req.label())?;
     webview.close_devtools();
     Ok(())
 }
+
+/// Runs `script` in the page without waiting for it.
+#[api]
+fn eval(script: String) -> Result<()> {
+    let webview = app.app_context()?.get_webview(req.label())?;
+    webview.evaluate_script(&script)?;
+    Ok(())
+}
+
+/// Runs `script` in the page and resolves with the value of its last
+/// expression, as far as it survives `JSON.stringify`. Fails with a timeout
+/// error when the page does not answer within `timeout` milliseconds, e.g.
+/// because the script threw.
+#[api(async)]
+async fn eval_with_result(script: String, timeout: Option<u64>) -> Result<Value> {
+    let label = req.label().map(str::to_string);
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.run_on_main(move |app, _, _| {
+        let webview = app.app_context()?.get_webview(label.as_deref())?;
+        // the callback is `Fn`, but the page answers only once
+        let tx = Mutex::new(Some(tx));
+        webview.evaluate_script_with_callback(&script, move |result| {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(result);
+            }
+        })?;
+        Ok(())
+    })
+    .await?;
+
+    let timeout = timeout.unwrap_or(EVAL_TIMEOUT_MS);
+    let result = match tokio::time::timeout(Duration::from_millis(timeout), rx).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => return Err(ApiError::Cancelled.into()),
+        Err(_) => return Err(ApiError::Timeout(timeout).into()),
+    };
+    // `undefined` comes back empty, anything unparsable as the raw string
+    if result.is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&result).unwrap_or(Value::String(result)))
+}
+
+/// Dispatches `event` to the listeners of the page, see `events.js`.
+#[api]
+fn emit(event: String, payload: Payload) -> Result<()> {
+    let webview = app.app_context()?.get_webview(req.label())?;
+    webview.evaluate_script(&emit_script(&event, &payload)?)?;
+    Ok(())
+}
+
+/// Dispatches `event` to the listeners of every open page. Every page gets
+/// it even when one of them fails; those are reported together afterwards.
+#[api]
+fn broadcast(event: String, payload: Payload) -> Result<()> {
+    let script = emit_script(&event, &payload)?;
+    let ctx = app.app_context()?;
+    let mut failed = Vec::new();
+    for label in ctx.labels() {
+        // a window that is just closing misses the event
+        let Ok(webview) = ctx.get_webview(Some(label.as_str())) else {
+            continue;
+        };
+        if let Err(e) = webview.evaluate_script(&script) {
+            failed.push(format!("{}: {}", label, e));
+        }
+    }
+    if !failed.is_empty() {
+        return Err(
+            ApiError::Internal(format!("event not delivered to {}", failed.join(", "))).into(),
+        );
+    }
+    Ok(())
+}
+
+fn emit_script(event: &str, payload: &Payload) -> Result<String> {
+    Ok(format!(
+        "window.__PYORION_EVENTS__ && window.__PYORION_EVENTS__.dispatch({}, {})",
+        serde_json::to_string(event)?,
+        serde_json::to_string(payload)?
+    ))
+}
+
+/// Answers the `invoke` call `id` of the page, with `result` or, when set,
+/// the error message of the Python command.
+#[api]
+fn respond(id: u64, result: Payload, error: Option<String>) -> Result<()> {
+    let resp = match error {
+        Some(msg) => ApiResponse::error(id, &ApiError::Internal(msg)),
+        None => ApiResponse(id, code::OK, "ok".to_string(), result),
+    };
+    crate::window::ipc::reply(&app, req.label(), &resp);
+    Ok(())
+}
@@ -24,22 +24,16 @@ set_window_effects
 ///
 #[api]
 fn set_window_effects(effects: WindowEffectsConfig) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        let _ = effect(&window, Some(effects));
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    let _ = effect(&window, Some(effects));
+    Ok(true)
 }
 
 #[api]
 fn set_visible(visible: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_visible(visible);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_visible(visible);
+    Ok(true)
 }
 
 /// Sets the title of the window.
@@ -50,12 +44,9 @@ fn set_visible(visible: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_title(title: String) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_title(&title);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_title(&title);
+    Ok(true)
 }
 
 /// Gets the current title of the window.
@@ -66,7 +57,7 @@ fn set_title(title: String) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns empty string.
 #[api]
 fn get_title() -> Result<String> {
-    let window = app.app_context()?.get_window()?;
+    let window = app.app_context()?.get_window(req.label())?;
     Ok(window.title())
 }
 
@@ -79,7 +70,7 @@ fn get_title() -> Result<String> {
 /// - iOS: Must be called on main thread.
 #[api]
 fn scale_factor() -> Result<f64> {
-    let window = app.app_context()?.get_window()?;
+    let window = app.app_context()?.get_window(req.label())?;
     Ok(window.scale_factor())
 }
 
@@ -92,12 +83,9 @@ fn scale_factor() -> Result<f64> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_always_on_bottom(always_on_bottom: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_always_on_bottom(always_on_bottom);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_always_on_bottom(always_on_bottom);
+    Ok(true)
 }
 
 /// Sets whether the window is always kept on top.
@@ -108,12 +96,9 @@ fn set_always_on_bottom(always_on_bottom: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_always_on_top(always_on_top: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_always_on_top(always_on_top);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_always_on_top(always_on_top);
+    Ok(true)
 }
 
 /// Sets the background color of the window.
@@ -125,12 +110,9 @@ fn set_always_on_top(always_on_top: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_background_color(color: Option<wry::RGBA>) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_background_color(color);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_background_color(color);
+    Ok(true)
 }
 
 /// Sets whether the window is closable.
@@ -142,12 +124,9 @@ fn set_background_color(color: Option<wry::RGBA>) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_closable(closable: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_closable(closable);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_closable(closable);
+    Ok(true)
 }
 
 /// Prevents window content capture.
@@ -158,12 +137,9 @@ fn set_closable(closable: bool) -> Result<bool> {
 /// - iOS / Android / Linux: Unsupported → returns `false`.
 #[api]
 fn set_content_protection(enabled: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_content_protection(enabled);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_content_protection(enabled);
+    Ok(true)
 }
 
 /// Grabs the cursor inside the window.
@@ -175,11 +151,8 @@ fn set_content_protection(enabled: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_cursor_grab(grab: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        Ok(window.set_cursor_grab(grab).is_ok())
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    Ok(window.set_cursor_grab(grab).is_ok())
 }
 
 /// Sets the cursor icon.
@@ -190,12 +163,9 @@ fn set_cursor_grab(grab: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_cursor_icon(cursor: pyorion_options::window::CursorIcon) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_cursor_icon(cursor.into());
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_cursor_icon(cursor.into());
+    Ok(true)
 }
 
 /// Sets the cursor position in window coordinates.
@@ -206,11 +176,8 @@ fn set_cursor_icon(cursor: pyorion_options::window::CursorIcon) -> Result<bool>
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_cursor_position(position: pyorion_options::window::Position) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        Ok(window.set_cursor_position(position).is_ok())
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    Ok(window.set_cursor_position(position).is_ok())
 }
 
 /// Sets cursor visibility.
@@ -223,12 +190,9 @@ fn set_cursor_position(position: pyorion_options::window::Position) -> Result<bo
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_cursor_visible(visible: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_cursor_visible(visible);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_cursor_visible(visible);
+    Ok(true)
 }
 
 /// Sets window decorations.
@@ -239,12 +203,9 @@ fn set_cursor_visible(visible: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_decorations(decorations: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_decorations(decorations);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_decorations(decorations);
+    Ok(true)
 }
 
 /// Focuses the window.
@@ -255,12 +216,9 @@ fn set_decorations(decorations: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_focus() -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_focus();
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_focus();
+    Ok(true)
 }
 
 /// Sets focusable state.
@@ -272,12 +230,9 @@ fn set_focus() -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_focusable(focusable: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_focusable(focusable);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_focusable(focusable);
+    Ok(true)
 }
 
 /// Returns list of available monitors.
@@ -288,7 +243,7 @@ fn set_focusable(focusable: bool) -> Result<bool> {
 /// - iOS: Main thread only.
 #[api]
 fn get_available_monitors() -> Result<Vec<pyorion_options::window::Monitor>> {
-    let window = app.app_context()?.get_window()?;
+    let window = app.app_context()?.get_window(req.label())?;
     let d = window
         .available_monitors()
         .map(|m| pyorion_options::window::Monitor {
@@ -330,16 +285,13 @@ fn get_available_monitors() -> Result<Vec<pyorion_options::window::Monitor>> {
 /// - Android: Unsupported → returns `false`.
 #[api]
 fn set_fullscreen(fullscreen: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        if fullscreen {
-            window.set_fullscreen(Some(tao::window::Fullscreen::Borderless(None)));
-        } else {
-            window.set_fullscreen(None);
-        }
-        Ok(true)
+    let window = app.app_context()?.get_window(req.label())?;
+    if fullscreen {
+        window.set_fullscreen(Some(tao::window::Fullscreen::Borderless(None)));
     } else {
-        Ok(false)
+        window.set_fullscreen(None);
     }
+    Ok(true)
 }
 
 /// Ignores or catches cursor events.
@@ -350,11 +302,8 @@ fn set_fullscreen(fullscreen: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_ignore_cursor_events(ignore: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        Ok(window.set_ignore_cursor_events(ignore).is_ok())
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    Ok(window.set_ignore_cursor_events(ignore).is_ok())
 }
 
 /// Sets IME candidate box position.
@@ -365,12 +314,9 @@ fn set_ignore_cursor_events(ignore: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_ime_position(position: pyorion_options::window::Position) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_ime_position(position);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_ime_position(position);
+    Ok(true)
 }
 
 /// Sets progress bar state.
@@ -382,12 +328,9 @@ fn set_ime_position(position: pyorion_options::window::Position) -> Result<bool>
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_progress_bar(progress: pyorion_options::window::ProgressBarState) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_progress_bar(progress.into());
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_progress_bar(progress.into());
+    Ok(true)
 }
 
 /// Sets inner size.
@@ -398,12 +341,9 @@ fn set_progress_bar(progress: pyorion_options::window::ProgressBarState) -> Resu
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_inner_size(size: pyorion_options::window::Size) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_inner_size(size);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_inner_size(size);
+    Ok(true)
 }
 
 /// Sets inner size constraints.
@@ -416,12 +356,9 @@ fn set_inner_size(size: pyorion_options::window::Size) -> Result<bool> {
 fn set_inner_size_constraints(
     constraints: pyorion_options::window::WindowSizeConstraints,
 ) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_inner_size_constraints(constraints.into());
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_inner_size_constraints(constraints.into());
+    Ok(true)
 }
 
 /// Sets max inner size.
@@ -432,12 +369,9 @@ fn set_inner_size_constraints(
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_max_inner_size(max_size: pyorion_options::window::Size) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_max_inner_size(Some(max_size));
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_max_inner_size(Some(max_size));
+    Ok(true)
 }
 
 /// Sets maximizable flag.
@@ -449,12 +383,9 @@ fn set_max_inner_size(max_size: pyorion_options::window::Size) -> Result<bool> {
 /// - Linux / iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_maximizable(maximizable: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_maximizable(maximizable);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_maximizable(maximizable);
+    Ok(true)
 }
 
 /// Minimizes or restores window.
@@ -465,12 +396,9 @@ fn set_maximizable(maximizable: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_minimized(minimized: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_minimized(minimized);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_minimized(minimized);
+    Ok(true)
 }
 
 /// Sets minimum inner size.
@@ -481,12 +409,9 @@ fn set_minimized(minimized: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_min_inner_size(min_size: pyorion_options::window::Size) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_min_inner_size(Some(min_size));
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_min_inner_size(Some(min_size));
+    Ok(true)
 }
 
 /// Sets minimizable flag.
@@ -497,12 +422,9 @@ fn set_min_inner_size(min_size: pyorion_options::window::Size) -> Result<bool> {
 /// - Linux / iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_minimizable(minimizable: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_minimizable(minimizable);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_minimizable(minimizable);
+    Ok(true)
 }
 
 /// Sets outer position.
@@ -513,12 +435,9 @@ fn set_minimizable(minimizable: bool) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_outer_position(position: pyorion_options::window::Position) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_outer_position(position);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_outer_position(position);
+    Ok(true)
 }
 
 /// Sets window theme.
@@ -529,16 +448,13 @@ fn set_outer_position(position: pyorion_options::window::Position) -> Result<boo
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_theme(theme: pyorion_options::window::Theme) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        let main_theme = match theme {
-            pyorion_options::window::Theme::Light => tao::window::Theme::Light,
-            pyorion_options::window::Theme::Dark => tao::window::Theme::Dark,
-        };
-        window.set_theme(Some(main_theme));
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    let main_theme = match theme {
+        pyorion_options::window::Theme::Light => tao::window::Theme::Light,
+        pyorion_options::window::Theme::Dark => tao::window::Theme::Dark,
+    };
+    window.set_theme(Some(main_theme));
+    Ok(true)
 }
 
 /// Sets whether visible on all workspaces.
@@ -549,24 +465,18 @@ fn set_theme(theme: pyorion_options::window::Theme) -> Result<bool> {
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_visible_on_all_workspaces(visible: bool) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_visible_on_all_workspaces(visible);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_visible_on_all_workspaces(visible);
+    Ok(true)
 }
 
 #[cfg(target_os = "windows")]
 #[api]
 fn set_enable(enable: bool) -> Result<bool> {
     use tao::platform::windows::WindowExtWindows;
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_enable(enable);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_enable(enable);
+    Ok(true)
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -579,12 +489,9 @@ fn set_enable(_enable: bool) -> Result<bool> {
 #[api]
 fn set_rtl(rtl: bool) -> Result<bool> {
     use tao::platform::windows::WindowExtWindows;
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_rtl(rtl);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_rtl(rtl);
+    Ok(true)
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -597,12 +504,9 @@ fn set_rtl(_rtl: bool) -> Result<bool> {
 #[api]
 fn set_undecorated_shadow(shadow: bool) -> Result<bool> {
     use tao::platform::windows::WindowExtWindows;
-    if let Ok(window) = app.app_context()?.get_window() {
-        window.set_undecorated_shadow(shadow);
-        Ok(true)
-    } else {
-        Ok(false)
-    }
+    let window = app.app_context()?.get_window(req.label())?;
+    window.set_undecorated_shadow(shadow);
+    Ok(true)
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -613,26 +517,83 @@ fn set_undecorated_shadow(_shadow: bool) -> Result<bool> {
 
 /// Returns inner size.
 #[api]
-fn inner_size() -> Result<tao::dpi::PhysicalSize<u32>> {
-    let window = app.app_context()?.get_window()?;
-    Ok(window.inner_size())
+fn inner_size() -> Result<pyorion_options::window::Dimensions> {
+    let window = app.app_context()?.get_window(req.label())?;
+    let size = window.inner_size();
+    Ok(pyorion_options::window::Dimensions {
+        width: size.width,
+        height: size.height,
+    })
 }
 
 /// Returns outer size.
 #[api]
-fn outer_size() -> Result<tao::dpi::PhysicalSize<u32>> {
-    let window = app.app_context()?.get_window()?;
-    Ok(window.outer_size())
+fn outer_size() -> Result<pyorion_options::window::Dimensions> {
+    let window = app.app_context()?.get_window(req.label())?;
+    let size = window.outer_size();
+    Ok(pyorion_options::window::Dimensions {
+        width: size.width,
+        height: size.height,
+    })
 }
 
 /// Returns outer position.
 #[api]
-fn outer_position() -> Result<tao::dpi::PhysicalPosition<i32>> {
-    let window = app.app_context()?.get_window()?;
-    Ok(window.outer_position()?)
+fn outer_position() -> Result<pyorion_options::window::MonitorPosition> {
+    let window = app.app_context()?.get_window(req.label())?;
+    let position = window.outer_position()?;
+    Ok(pyorion_options::window::MonitorPosition {
+        x: position.x,
+        y: position.y,
+    })
+}
+
+/// Creates a new window from `WindowOptions` and returns its label.
+///
+/// The label is taken from `webview.label`; a unique one is generated when
+/// it is missing.
+#[api]
+fn create(options: pyorion_options::window::WindowOptions) -> Result<String> {
+    let label = app
+        .app_context()?
+        .unique_label(options.webview.label.clone())?;
+    let (window_id, window, webview) = crate::window::create_frame(
+        target,
+        &options,
+        &label,
+        app.sock_cfg(),
+        app.proxy.clone(),
+        app.rt.handle().clone(),
+    )?;
+    app.app_context()?.add_window(
+        window_id,
+        label.clone(),
+        std::sync::Arc::new(window),
+        std::sync::Arc::new(webview),
+    )?;
+    Ok(label)
+}
+
+/// Closes the targeted window.
+///
+/// The application only shuts down when the last window is closed.
+#[api]
+fn close() -> Result<bool> {
+    let window_id = app.app_context()?.window_id(req.label())?;
+    app.close_window(window_id, flow)?;
+    Ok(true)
+}
+
+/// Returns the labels of all open windows in creation order.
+#[api]
+fn list() -> Result<Vec<String>> {
+    Ok(app.app_context()?.labels())
 }
 
 pub fn window_api(api_manager: &mut ApiManager) {
+    api_manager.register_api("window.create", create);
+    api_manager.register_api("window.close", close);
+    api_manager.register_api("window.list", list);
     api_manager.register_api("window.set_title", set_title);
     api_manager.register_api("window.get_title", get_title);
     api_manager.register_api("window.scale_factor", scale_factor);
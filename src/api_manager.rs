@@ -1,56 +1,152 @@
 use anyhow::Result;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
+    future::Future,
     sync::{Arc, Weak},
+    time::Duration,
 };
 
-use crate::{core::App, utils::FrameWindowTarget};
+use crate::{
+    binary::{from_payload, to_payload, Payload},
+    core::App,
+    error::ApiError,
+    schema,
+    utils::{FrameEventLoopProxy, FrameWindowTarget, UserEvent},
+};
 
 #[allow(dead_code)]
 #[derive(Deserialize, Clone, Debug)]
-pub struct ApiArguments(Value);
+pub struct ApiArguments(Payload);
 
 impl ApiArguments {
     #[allow(dead_code)]
     pub fn single<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
-        Ok(serde_json::from_value::<(T,)>(self.0.clone())?.0)
+        Ok(from_payload::<(T,)>(self.0.clone())?.0)
     }
     #[allow(dead_code)]
     pub fn get<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
-        Ok(serde_json::from_value(self.0.clone())?)
+        Ok(from_payload(self.0.clone())?)
     }
     #[allow(dead_code)]
     pub fn optional<T: serde::de::DeserializeOwned>(&self, args_size: usize) -> Result<T> {
-        let mut args = serde_json::from_value::<Vec<serde_json::Value>>(self.0.clone())?;
-        args.resize(args_size, json!(null));
-        let args = json!(args);
-        Ok(serde_json::from_value(args)?)
+        let mut args = from_payload::<Vec<Payload>>(self.0.clone())?;
+        args.resize(args_size, Payload::default());
+        let args = rmpv::Value::Array(args.into_iter().map(|arg| arg.0).collect());
+        Ok(from_payload(Payload(args))?)
+    }
+    /// Deserializes argument `index`, or the field `name` when the arguments
+    /// were passed as an object. A missing argument reads as `null`, so
+    /// `Option` parameters may be omitted.
+    #[allow(dead_code)]
+    pub fn arg<T: serde::de::DeserializeOwned>(
+        &self,
+        index: usize,
+        name: &str,
+    ) -> std::result::Result<T, ApiError> {
+        let value = match &self.0 .0 {
+            rmpv::Value::Array(args) => args.get(index).cloned(),
+            rmpv::Value::Map(args) => args
+                .iter()
+                .find(|(key, _)| key.as_str() == Some(name))
+                .map(|(_, value)| value.clone()),
+            rmpv::Value::Nil => None,
+            _ => {
+                return Err(ApiError::invalid_arguments(
+                    "arguments must be passed as an array or an object",
+                ))
+            }
+        };
+        let value = value.unwrap_or(rmpv::Value::Nil);
+        let missing = value.is_nil();
+        from_payload(Payload(value)).map_err(|e| {
+            let message = if missing {
+                "missing required argument".to_string()
+            } else {
+                e.to_string()
+            };
+            ApiError::invalid_argument(index, name, message)
+        })
+    }
+    /// Rejects object keys that name no parameter, so a typo is not silently
+    /// treated as an omitted argument. Positional arguments pass unchecked.
+    #[allow(dead_code)]
+    pub fn check_names(&self, names: &[&str]) -> std::result::Result<(), ApiError> {
+        let rmpv::Value::Map(args) = &self.0 .0 else {
+            return Ok(());
+        };
+        let unknown = args
+            .iter()
+            .map(|(key, _)| key)
+            .find(|key| !key.as_str().is_some_and(|key| names.contains(&key)));
+        match unknown {
+            Some(key) => Err(ApiError::InvalidArguments {
+                message: "unknown argument".to_string(),
+                argument: Some(key.as_str().map_or_else(|| key.to_string(), str::to_string)),
+                index: None,
+            }),
+            None => Ok(()),
+        }
     }
 }
+/// `[id, method, args, label?, timeout_ms?]`, where the optional label
+/// targets a window and the optional timeout bounds the wait for a response.
 #[allow(dead_code)]
 #[derive(Deserialize, Clone, Debug)]
-pub struct ApiRequest(pub u8, pub String, pub ApiArguments);
+pub struct ApiRequest(
+    pub u64,
+    pub String,
+    pub ApiArguments,
+    #[serde(default)] pub Option<String>,
+    #[serde(default)] pub Option<u64>,
+);
 
 impl ApiRequest {
     #[allow(dead_code)]
     pub fn err<C: Into<i32>, S: Into<String>>(&self, code: C, msg: S) -> ApiResponse {
-        ApiResponse(self.0, code.into(), msg.into(), json!(null))
+        ApiResponse(self.0, code.into(), msg.into(), Payload::default())
+    }
+    #[allow(dead_code)]
+    pub fn fail(&self, error: &ApiError) -> ApiResponse {
+        ApiResponse::error(self.0, error)
     }
     #[allow(dead_code)]
     pub fn ok<D: Serialize>(&self, data: D) -> ApiResponse {
-        ApiResponse(self.0, 0, "ok".to_string(), json!(data))
+        match to_payload(data) {
+            Ok(data) => self.ok_payload(data),
+            Err(e) => self.fail(&ApiError::Internal(e.to_string())),
+        }
+    }
+    /// [`ok`](ApiRequest::ok) for a result that is already a [`Payload`].
+    pub fn ok_payload(&self, data: Payload) -> ApiResponse {
+        ApiResponse(self.0, 0, "ok".to_string(), data)
     }
     #[allow(dead_code)]
     pub fn args(&self) -> &ApiArguments {
         &self.2
     }
+    #[allow(dead_code)]
+    pub fn label(&self) -> Option<&str> {
+        self.3.as_deref()
+    }
+    #[allow(dead_code)]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.4.map(Duration::from_millis)
+    }
 }
 pub type Code = i32;
 #[allow(dead_code)]
-#[derive(Serialize, Clone)]
-pub struct ApiResponse(pub u8, pub Code, pub String, pub Value);
+#[derive(Serialize, Clone, Debug, JsonSchema)]
+pub struct ApiResponse(pub u64, pub Code, pub String, pub Payload);
+
+impl ApiResponse {
+    /// `[id, code, msg, details]` for a failed request.
+    pub fn error(id: u64, error: &ApiError) -> Self {
+        ApiResponse(id, error.code(), error.to_string(), error.details().into())
+    }
+}
 #[allow(dead_code)]
 pub type ApiInstance = std::pin::Pin<
     Box<
@@ -62,10 +158,277 @@ pub type ApiInstance = std::pin::Pin<
         ) -> Result<ApiResponse>,
     >,
 >;
+/// Handler registered with [`ApiManager::register_async_api`]; runs on the
+/// tokio runtime instead of the event loop.
+pub type AsyncApiInstance = Arc<
+    dyn Fn(
+            AppHandle,
+            ApiRequest,
+            ChunkSink,
+        ) -> std::pin::Pin<Box<dyn Future<Output = ApiResponse> + Send>>
+        + Send
+        + Sync,
+>;
+/// An async handler as the connection layer sees it.
+#[derive(Clone)]
+pub struct AsyncApi {
+    /// Whether the handler sends its result in chunks, see [`ChunkSink`].
+    pub stream: bool,
+    pub run: AsyncApiInstance,
+}
+/// Snapshot of the async handlers, shared with the connection layer.
+pub type AsyncApis = Arc<HashMap<String, AsyncApi>>;
+
+/// How many chunks a streaming handler may produce before the connection
+/// forwarded them.
+const CHUNK_BUFFER: usize = 4;
+
+/// Sends the chunks of a streamed result; `#[api(stream)]` handlers get it
+/// as `chunks`.
+///
+/// Every chunk goes out as its own `[id, 100, "chunk", data]` frame, the
+/// handler's return value follows as the final response. [`send`] waits
+/// while the connection is behind and fails with a cancellation error once
+/// nobody reads the stream anymore, so a handler stops at the next chunk.
+///
+/// [`send`]: ChunkSink::send
+#[derive(Clone, Default)]
+pub struct ChunkSink(Option<tokio::sync::mpsc::Sender<Payload>>);
+
+impl ChunkSink {
+    /// A sink and the receiving end the connection forwards from.
+    pub fn channel() -> (Self, tokio::sync::mpsc::Receiver<Payload>) {
+        let (tx, rx) = tokio::sync::mpsc::channel(CHUNK_BUFFER);
+        (Self(Some(tx)), rx)
+    }
+
+    #[allow(dead_code)]
+    pub async fn send<T: Serialize>(&self, chunk: T) -> Result<()> {
+        let Some(tx) = &self.0 else {
+            return Err(ApiError::Internal("the request is not streamed".to_string()).into());
+        };
+        let chunk = to_result(chunk)?;
+        tx.send(chunk).await.map_err(|_| ApiError::Cancelled.into())
+    }
+
+    /// [`send`](ChunkSink::send) for work running in [`blocking`].
+    #[allow(dead_code)]
+    pub fn blocking_send<T: Serialize>(&self, chunk: T) -> Result<()> {
+        let Some(tx) = &self.0 else {
+            return Err(ApiError::Internal("the request is not streamed".to_string()).into());
+        };
+        let chunk = to_result(chunk)?;
+        tx.blocking_send(chunk)
+            .map_err(|_| ApiError::Cancelled.into())
+    }
+}
+
+/// Thread-safe handle passed to async handlers.
+///
+/// Anything that touches a window or webview has to run on the main thread
+/// and goes through [`AppHandle::run_on_main`].
+#[derive(Clone)]
+pub struct AppHandle {
+    proxy: FrameEventLoopProxy,
+}
+
+impl AppHandle {
+    pub fn new(proxy: FrameEventLoopProxy) -> Self {
+        Self { proxy }
+    }
+
+    /// Runs `f` inside the event loop and resolves with its result.
+    ///
+    /// The task is queued right away; the returned future does not borrow
+    /// the handle.
+    #[allow(dead_code)]
+    pub fn run_on_main<T, F>(&self, f: F) -> impl Future<Output = Result<T>> + Send + 'static
+    where
+        T: Send + 'static,
+        F: FnOnce(&Arc<App>, &FrameWindowTarget, &mut tao::event_loop::ControlFlow) -> Result<T>
+            + Send
+            + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let sent = self
+            .proxy
+            .send_event(UserEvent::Dispatch(Box::new(move |app, target, flow| {
+                let _ = tx.send(f(app, target, flow));
+            })))
+            .map_err(|_| ApiError::Internal("the event loop is closed".to_string()));
+        async move {
+            sent?;
+            rx.await
+                .map_err(|_| ApiError::Internal("the event loop dropped the task".to_string()))?
+        }
+    }
+}
+
+/// Runs blocking work (file system, hashing, ...) on tokio's blocking pool.
+#[allow(dead_code)]
+pub async fn blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+}
+
+/// Method running several event-loop calls in one dispatch, see
+/// [`ApiManager::call_batch`].
+pub const BATCH_METHOD: &str = "system.batch";
+
+const BATCH_PARAMS: &[ApiParam] = &[
+    ApiParam {
+        name: "calls",
+        ty: "Vec<BatchCall>",
+        optional: false,
+        schema: schema::arg_schema::<Vec<BatchCall>>,
+    },
+    ApiParam {
+        name: "stop_on_error",
+        ty: "Option<bool>",
+        optional: true,
+        schema: schema::arg_schema::<Option<bool>>,
+    },
+];
+
+/// One call of a batch, `[method, args?, label?]` or the same as an object.
+/// Without a label it targets the window of the batch request.
+#[derive(Deserialize, JsonSchema, Debug)]
+pub struct BatchCall {
+    method: String,
+    #[serde(default)]
+    args: Option<Payload>,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// One parameter of a handler, as written in its signature.
+#[derive(Clone, Copy, Debug)]
+pub struct ApiParam {
+    pub name: &'static str,
+    pub ty: &'static str,
+    pub optional: bool,
+    /// JSON Schema of `ty`, see [`schema::arg_schema`].
+    pub schema: fn() -> Value,
+}
+
+/// Handler generated by `#[api]`; runs on the event loop.
+pub trait ApiHandler: 'static {
+    fn params(&self) -> &'static [ApiParam];
+    fn result(&self) -> &'static str;
+    fn result_schema(&self) -> Value;
+    fn doc(&self) -> &'static str;
+    fn call(
+        &self,
+        app: Arc<App>,
+        req: ApiRequest,
+        target: &FrameWindowTarget,
+        flow: &mut tao::event_loop::ControlFlow,
+    ) -> Result<Payload>;
+}
+
+/// Handler generated by `#[api(async)]` or `#[api(stream)]`; runs on the
+/// tokio runtime.
+pub trait AsyncApiHandler: Send + Sync + 'static {
+    fn params(&self) -> &'static [ApiParam];
+    fn result(&self) -> &'static str;
+    fn result_schema(&self) -> Value;
+    fn doc(&self) -> &'static str;
+    /// Whether the handler sends chunks through the [`ChunkSink`].
+    fn stream(&self) -> bool {
+        false
+    }
+    fn call(
+        &self,
+        app: AppHandle,
+        req: ApiRequest,
+        chunks: ChunkSink,
+    ) -> std::pin::Pin<Box<dyn Future<Output = Result<Payload>> + Send>>;
+}
+
+/// Converts a handler's result for the response. Failing to serialize our
+/// own value is an internal error, not an argument error.
+pub fn to_result<T: Serialize>(data: T) -> Result<Payload> {
+    to_payload(data).map_err(|e| ApiError::Internal(e.to_string()).into())
+}
+
+/// What `system.describe` reports about a registered method.
+#[derive(Clone)]
+struct ApiDescription {
+    is_async: bool,
+    stream: bool,
+    params: &'static [ApiParam],
+    result: &'static str,
+    result_schema: Value,
+    doc: &'static str,
+}
+
+impl ApiDescription {
+    fn to_json(&self) -> Value {
+        let params: Vec<Value> = self
+            .params
+            .iter()
+            .map(|p| {
+                json!({
+                    "name": p.name,
+                    "type": p.ty,
+                    "optional": p.optional,
+                    "schema": (p.schema)(),
+                })
+            })
+            .collect();
+        let schemas: Vec<Value> = self.params.iter().map(|p| (p.schema)()).collect();
+        let properties: serde_json::Map<String, Value> = self
+            .params
+            .iter()
+            .map(|p| (p.name.to_string(), (p.schema)()))
+            .collect();
+        let required_names: Vec<&str> = self
+            .params
+            .iter()
+            .filter(|p| !p.optional)
+            .map(|p| p.name)
+            .collect();
+        let required = self
+            .params
+            .iter()
+            .rposition(|p| !p.optional)
+            .map_or(0, |i| i + 1);
+        json!({
+            "async": self.is_async,
+            "stream": self.stream,
+            "doc": self.doc,
+            "params": params,
+            "argsSchema": {
+                "type": "array",
+                "prefixItems": schemas,
+                "minItems": required,
+                "maxItems": self.params.len(),
+            },
+            "kwargsSchema": {
+                "type": "object",
+                "properties": properties,
+                "required": required_names,
+                "additionalProperties": false,
+            },
+            "result": {
+                "type": self.result,
+                "schema": self.result_schema,
+            },
+        })
+    }
+}
+
 #[allow(dead_code)]
 pub struct ApiManager {
     ctx: Option<Weak<App>>,
     api_instance: HashMap<String, ApiInstance>,
+    async_instance: HashMap<String, AsyncApi>,
+    descriptions: BTreeMap<String, ApiDescription>,
 }
 
 impl ApiManager {
@@ -74,6 +437,8 @@ impl ApiManager {
         let _self = Self {
             ctx: None,
             api_instance: HashMap::new(),
+            async_instance: HashMap::new(),
+            descriptions: BTreeMap::new(),
         };
         crate::utils::arc_mut(_self)
     }
@@ -82,27 +447,139 @@ impl ApiManager {
         self.ctx = Some(Arc::downgrade(ctx));
     }
     #[allow(dead_code)]
-    pub fn register_api<S: Into<String>, T: Serialize + 'static>(
-        &mut self,
-        name: S,
-        api_func: fn(
-            Arc<App>,
-            ApiRequest,
-            &FrameWindowTarget,
-            &mut tao::event_loop::ControlFlow,
-        ) -> Result<T>,
-    ) {
+    pub fn register_api<S: Into<String>, H: ApiHandler>(&mut self, name: S, handler: H) {
+        let name = name.into();
+        self.describe(
+            &name,
+            ApiDescription {
+                is_async: false,
+                stream: false,
+                params: handler.params(),
+                result: handler.result(),
+                result_schema: handler.result_schema(),
+                doc: handler.doc(),
+            },
+        );
         let api_instance: ApiInstance = Box::pin(move |ctx: Arc<App>, request, target, flow| {
-            let result = api_func(ctx, request.clone(), target, flow);
+            // a panicking handler must not take the event loop down with it
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                handler.call(ctx, request.clone(), target, flow)
+            }));
             let response = match result {
-                Ok(data) => request.ok(data),
-                Err(err) => request.err(-1, err.to_string()),
+                Ok(Ok(data)) => request.ok_payload(data),
+                Ok(Err(err)) => request.fail(&ApiError::from_anyhow(&err)),
+                Err(_) => request.fail(&ApiError::Internal(format!(
+                    "handler for {} panicked",
+                    request.1
+                ))),
             };
 
             Ok(response)
         });
 
-        self.api_instance.insert(name.into(), api_instance);
+        self.api_instance.insert(name, api_instance);
+    }
+    /// Registers a handler generated by `#[api(async)]` or `#[api(stream)]`.
+    #[allow(dead_code)]
+    pub fn register_async_api<S: Into<String>, H: AsyncApiHandler>(&mut self, name: S, handler: H) {
+        let name = name.into();
+        let stream = handler.stream();
+        self.describe(
+            &name,
+            ApiDescription {
+                is_async: true,
+                stream,
+                params: handler.params(),
+                result: handler.result(),
+                result_schema: handler.result_schema(),
+                doc: handler.doc(),
+            },
+        );
+        let api_instance: AsyncApiInstance =
+            Arc::new(move |handle, request: ApiRequest, chunks| {
+                let fut = handler.call(handle, request.clone(), chunks);
+                Box::pin(async move {
+                    match fut.await {
+                        Ok(data) => request.ok_payload(data),
+                        Err(err) => request.fail(&ApiError::from_anyhow(&err)),
+                    }
+                })
+            });
+
+        self.async_instance.insert(
+            name,
+            AsyncApi {
+                stream,
+                run: api_instance,
+            },
+        );
+    }
+    fn describe(&mut self, name: &str, description: ApiDescription) {
+        self.descriptions.insert(name.to_string(), description);
+    }
+    /// `{"methods": {name: description}}` for every registered method.
+    #[allow(dead_code)]
+    pub fn descriptions(&self) -> Value {
+        let methods: serde_json::Map<String, Value> = self
+            .descriptions
+            .iter()
+            .map(|(name, desc)| (name.clone(), desc.to_json()))
+            .collect();
+        json!({ "methods": methods })
+    }
+    /// Namespaces of the registered methods, the part before the first dot.
+    #[allow(dead_code)]
+    pub fn namespaces(&self) -> Vec<String> {
+        let namespaces: std::collections::BTreeSet<&str> = self
+            .descriptions
+            .keys()
+            .map(|name| name.split('.').next().unwrap_or(name))
+            .collect();
+        namespaces.into_iter().map(str::to_string).collect()
+    }
+    #[allow(dead_code)]
+    pub fn async_apis(&mut self) -> AsyncApis {
+        self.describe(
+            BATCH_METHOD,
+            ApiDescription {
+                is_async: false,
+                stream: false,
+                params: BATCH_PARAMS,
+                result: "Vec<ApiResponse>",
+                result_schema: schema::result_schema::<Vec<ApiResponse>>(),
+                doc: "Runs calls in order within one event-loop turn and returns an \
+                      `[index, code, msg, result]` response per call.",
+            },
+        );
+        // described first so the snapshot it serves includes itself
+        self.describe(
+            "system.describe",
+            ApiDescription {
+                is_async: true,
+                stream: false,
+                params: &[],
+                result: "Value",
+                result_schema: schema::result_schema::<Value>(),
+                doc: "Lists every registered method with its parameters and result.",
+            },
+        );
+        let description = Arc::new(self.descriptions());
+        let mut apis = self.async_instance.clone();
+        apis.insert(
+            "system.describe".to_string(),
+            AsyncApi {
+                stream: false,
+                run: Arc::new(move |_, request: ApiRequest, _| {
+                    let description = description.clone();
+                    Box::pin(async move { request.ok(description.as_ref()) })
+                }),
+            },
+        );
+        Arc::new(apis)
+    }
+    /// Whether `name` is answered by [`call`](Self::call).
+    pub fn has_api(&self, name: &str) -> bool {
+        name == BATCH_METHOD || self.api_instance.contains_key(name)
     }
     #[allow(dead_code)]
     pub fn call(
@@ -111,6 +588,9 @@ impl ApiManager {
         target: &FrameWindowTarget,
         flow: &mut tao::event_loop::ControlFlow,
     ) -> anyhow::Result<ApiResponse> {
+        if req.1 == BATCH_METHOD {
+            return Ok(self.call_batch(req, target, flow));
+        }
         if let Some(handler) = self.api_instance.get(&req.1) {
             if let Some(ctx) = self.ctx.as_ref().and_then(|w| w.upgrade()) {
                 handler(ctx, req.clone(), target, flow)
@@ -118,7 +598,280 @@ impl ApiManager {
                 Err(anyhow::anyhow!("App reference not available"))
             }
         } else {
-            Err(anyhow::anyhow!("Unknown method: {}", req.1))
+            Ok(req.fail(&ApiError::UnknownMethod(req.1.clone())))
+        }
+    }
+    /// Runs the calls of a `system.batch` request one after another, so a
+    /// whole layout is applied without repainting in between.
+    ///
+    /// Every call gets its own `[index, code, msg, result]` response; a call
+    /// without a label targets the window of the batch request. Async
+    /// methods run off the event loop and cannot be batched, nor can
+    /// `system.batch` itself.
+    ///
+    /// With `stop_on_error` every call is checked before the first one runs.
+    /// When one names an unknown, async or batch method, it is answered with
+    /// that error and nothing runs; the other calls are cancelled. Once the
+    /// calls run, a failing one cancels those after it. Nothing is rolled
+    /// back: the calls before a failure stay applied.
+    fn call_batch(
+        &mut self,
+        req: ApiRequest,
+        target: &FrameWindowTarget,
+        flow: &mut tao::event_loop::ControlFlow,
+    ) -> ApiResponse {
+        let (calls, stop_on_error) = match batch_calls(&req) {
+            Ok(batch) => batch,
+            Err(err) => return req.fail(&err),
+        };
+        let calls = calls
+            .into_iter()
+            .map(|call| {
+                let rejected = self.batch_error(&call.1);
+                (call, rejected)
+            })
+            .collect();
+        req.ok(run_batch(calls, stop_on_error, |call| {
+            self.call(call, target, flow)
+        }))
+    }
+    /// Why `method` cannot be part of a batch, if it cannot.
+    fn batch_error(&self, method: &str) -> Option<ApiError> {
+        if method == BATCH_METHOD || self.async_instance.contains_key(method) {
+            Some(ApiError::invalid_arguments(format!(
+                "{} does not run on the event loop and cannot be batched",
+                method
+            )))
+        } else if !self.api_instance.contains_key(method) {
+            Some(ApiError::UnknownMethod(method.to_string()))
+        } else {
+            None
         }
     }
 }
+
+/// The calls of a `system.batch` request as requests of their own, with
+/// their index as id, and its `stop_on_error` flag.
+fn batch_calls(req: &ApiRequest) -> std::result::Result<(Vec<ApiRequest>, bool), ApiError> {
+    let args = req.args();
+    args.check_names(&["calls", "stop_on_error"])?;
+    let calls = args.arg::<Vec<BatchCall>>(0, "calls")?;
+    let stop_on_error = args.arg::<Option<bool>>(1, "stop_on_error")?;
+    let calls = calls
+        .into_iter()
+        .enumerate()
+        .map(|(index, call)| {
+            ApiRequest(
+                index as u64,
+                call.method,
+                ApiArguments(call.args.unwrap_or_else(|| json!([]).into())),
+                call.label.or_else(|| req.3.clone()),
+                None,
+            )
+        })
+        .collect();
+    Ok((calls, stop_on_error.unwrap_or(false)))
+}
+
+/// Answers the calls of a batch, each with the error it was rejected with,
+/// if any; `run` runs one that was not. See [`ApiManager::call_batch`].
+fn run_batch<F>(
+    calls: Vec<(ApiRequest, Option<ApiError>)>,
+    stop_on_error: bool,
+    mut run: F,
+) -> Vec<ApiResponse>
+where
+    F: FnMut(ApiRequest) -> Result<ApiResponse>,
+{
+    let mut failed = stop_on_error && calls.iter().any(|(_, rejected)| rejected.is_some());
+    calls
+        .into_iter()
+        .map(|(call, rejected)| {
+            if let Some(err) = rejected {
+                return call.fail(&err);
+            }
+            if failed {
+                return call.fail(&ApiError::Cancelled);
+            }
+            let resp =
+                run(call.clone()).unwrap_or_else(|err| call.fail(&ApiError::from_anyhow(&err)));
+            failed |= stop_on_error && resp.1 != crate::error::code::OK;
+            resp
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::code::{CANCELLED, INVALID_ARGUMENTS, OK, UNKNOWN_METHOD};
+
+    const PARAMS: &[ApiParam] = &[
+        ApiParam {
+            name: "title",
+            ty: "String",
+            optional: false,
+            schema: schema::arg_schema::<String>,
+        },
+        ApiParam {
+            name: "limit",
+            ty: "Option<u32>",
+            optional: true,
+            schema: schema::arg_schema::<Option<u32>>,
+        },
+    ];
+
+    fn description() -> Value {
+        ApiDescription {
+            is_async: true,
+            stream: false,
+            params: PARAMS,
+            result: "Vec<String>",
+            result_schema: schema::result_schema::<Vec<String>>(),
+            doc: "Lists things.",
+        }
+        .to_json()
+    }
+
+    #[test]
+    fn describes_positional_arguments() {
+        let args = &description()["argsSchema"];
+        assert_eq!(args["type"], "array");
+        assert_eq!(args["minItems"], 1);
+        assert_eq!(args["maxItems"], 2);
+        assert_eq!(args["prefixItems"][0], json!({ "type": "string" }));
+        assert_eq!(
+            args["prefixItems"][1]["anyOf"][1],
+            json!({ "type": "null" })
+        );
+    }
+
+    #[test]
+    fn describes_named_arguments() {
+        let kwargs = &description()["kwargsSchema"];
+        assert_eq!(kwargs["type"], "object");
+        assert_eq!(kwargs["required"], json!(["title"]));
+        assert_eq!(kwargs["additionalProperties"], false);
+        assert_eq!(kwargs["properties"]["title"], json!({ "type": "string" }));
+        assert!(kwargs["properties"]["limit"]["anyOf"].is_array());
+    }
+
+    #[test]
+    fn describes_params_and_result() {
+        let desc = description();
+        assert_eq!(desc["async"], true);
+        assert_eq!(desc["stream"], false);
+        assert_eq!(desc["doc"], "Lists things.");
+        assert_eq!(desc["params"][1]["name"], "limit");
+        assert_eq!(desc["params"][1]["type"], "Option<u32>");
+        assert_eq!(desc["params"][1]["optional"], true);
+        assert_eq!(desc["result"]["type"], "Vec<String>");
+        assert_eq!(
+            desc["result"]["schema"],
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+    }
+
+    fn batch(calls: Value, stop_on_error: bool) -> ApiRequest {
+        ApiRequest(
+            7,
+            BATCH_METHOD.to_string(),
+            ApiArguments(json!([calls, stop_on_error]).into()),
+            Some("main".to_string()),
+            None,
+        )
+    }
+
+    fn codes(responses: &[ApiResponse]) -> Vec<Code> {
+        responses.iter().map(|resp| resp.1).collect()
+    }
+
+    #[test]
+    fn batch_calls_inherit_the_batch_label() {
+        let calls = json!([
+            { "method": "window.show" },
+            { "method": "window.hide", "label": "other", "args": [true] },
+        ]);
+        let (calls, stop_on_error) = batch_calls(&batch(calls, true)).unwrap();
+        assert!(stop_on_error);
+        assert_eq!((calls[0].0, calls[0].label()), (0, Some("main")));
+        assert_eq!((calls[1].0, calls[1].label()), (1, Some("other")));
+        assert_eq!(calls[1].args().0.to_json(), json!([true]));
+    }
+
+    #[test]
+    fn batch_rejects_nested_batches_and_async_methods() {
+        let manager = ApiManager::new();
+        let mut manager = manager.lock().unwrap();
+        manager.api_instance.insert(
+            "window.show".to_string(),
+            Box::pin(|_, req: ApiRequest, _: &FrameWindowTarget, _: &mut _| Ok(req.ok(()))),
+        );
+        manager.async_instance.insert(
+            "resource.read".to_string(),
+            AsyncApi {
+                stream: false,
+                run: Arc::new(|_, req: ApiRequest, _| Box::pin(async move { req.ok(()) })),
+            },
+        );
+        assert!(manager.batch_error("window.show").is_none());
+        for method in [BATCH_METHOD, "resource.read"] {
+            let err = manager.batch_error(method).unwrap();
+            assert_eq!(err.code(), INVALID_ARGUMENTS);
+        }
+        let err = manager.batch_error("window.nope").unwrap();
+        assert_eq!(err.code(), UNKNOWN_METHOD);
+    }
+
+    #[test]
+    fn stop_on_error_cancels_the_calls_after_a_failure() {
+        let calls = json!([
+            { "method": "a" },
+            { "method": "fail" },
+            { "method": "b" },
+        ]);
+        for (stop_on_error, expected, ran) in [
+            (
+                true,
+                vec![OK, INVALID_ARGUMENTS, CANCELLED],
+                vec!["a", "fail"],
+            ),
+            (
+                false,
+                vec![OK, INVALID_ARGUMENTS, OK],
+                vec!["a", "fail", "b"],
+            ),
+        ] {
+            let (calls, _) = batch_calls(&batch(calls.clone(), stop_on_error)).unwrap();
+            let calls = calls.into_iter().map(|call| (call, None)).collect();
+            let mut names = Vec::new();
+            let results = run_batch(calls, stop_on_error, |call| {
+                names.push(call.1.clone());
+                if call.1 == "fail" {
+                    return Err(ApiError::invalid_arguments("no").into());
+                }
+                Ok(call.ok(()))
+            });
+            assert_eq!(codes(&results), expected);
+            assert_eq!(names, ran);
+        }
+    }
+
+    #[test]
+    fn stop_on_error_runs_nothing_when_a_call_is_rejected() {
+        let calls = json!([{ "method": "a" }, { "method": "nested" }, { "method": "b" }]);
+        let (calls, stop_on_error) = batch_calls(&batch(calls, true)).unwrap();
+        let calls = calls
+            .into_iter()
+            .map(|call| {
+                let rejected = (call.1 == "nested").then(|| ApiError::invalid_arguments("no"));
+                (call, rejected)
+            })
+            .collect();
+        let results = run_batch(calls, stop_on_error, |_| panic!("nothing may run"));
+        assert_eq!(
+            codes(&results),
+            vec![CANCELLED, INVALID_ARGUMENTS, CANCELLED]
+        );
+    }
+}
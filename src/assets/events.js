@@ -0,0 +1,67 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * PyOrion frontend event bus.
+ *
+ * Delivers the events Python pushes with `webview.emit(event, payload)`.
+ *
+ * Features:
+ *  - `listen(event, handler)` returns a function removing the handler.
+ *  - `once(event, handler)` removes the handler after its first call.
+ *  - `unlisten(event, handler?)` removes one handler or all of an event.
+ *  - Every event is also dispatched on `window` as a `pyorion:<event>`
+ *    `CustomEvent` carrying the payload in `detail`.
+ */
+(function () {
+  const listeners = new Map();
+
+  function listen(event, handler) {
+    if (!listeners.has(event)) listeners.set(event, new Set());
+    listeners.get(event).add(handler);
+    return () => unlisten(event, handler);
+  }
+
+  function once(event, handler) {
+    const unlistenOnce = listen(event, (payload) => {
+      unlistenOnce();
+      handler(payload);
+    });
+    return unlistenOnce;
+  }
+
+  function unlisten(event, handler) {
+    const handlers = listeners.get(event);
+    if (!handlers) return;
+    if (handler) {
+      handlers.delete(handler);
+    } else {
+      handlers.clear();
+    }
+    if (handlers.size === 0) listeners.delete(event);
+  }
+
+  /** Called by the native side for every emitted event. */
+  function dispatch(event, payload) {
+    // a handler may unlisten while we iterate
+    for (const handler of Array.from(listeners.get(event) ?? [])) {
+      try {
+        handler(payload);
+      } catch (err) {
+        console.error(`Listener for "${event}" failed:`, err);
+      }
+    }
+    window.dispatchEvent(new CustomEvent(`pyorion:${event}`, { detail: payload }));
+  }
+
+  Object.defineProperty(window, "__PYORION_EVENTS__", {
+    value: Object.freeze({ dispatch }),
+    writable: false,
+    configurable: false,
+  });
+
+  window.listen = listen;
+  window.once = once;
+  window.unlisten = unlisten;
+})();
@@ -5,21 +5,16 @@
 /**
  * PyOrion frontend connection bootstrap.
  *
- * Provides a Promise-based `invoke(cmd, args)` API that communicates
- * with the backend via PyOrionConnections (WebSocket).
+ * Provides a Promise-based `invoke(cmd, args)` API that posts straight
+ * to the native event loop through `window.ipc`, without a socket.
  *
  * Features:
- *  - Auto reconnect with configurable interval.
- *  - Unique ID mapping for result/error callbacks.
- *  - Automatic cleanup of one-time callbacks.
+ *  - Incremental ids mapping every response to its pending promise.
+ *  - Rejections carry the error `code` and `details` of the response.
  *  - Global `window.invoke` helper for command dispatch.
  */
 (function () {
 
-  function uid() {
-    return window.crypto.getRandomValues(new Uint32Array(1))[0];
-  }
-
   function jsonMakeObjectSafe(obj) {
     if (obj === null || obj === undefined) return null;
     if (typeof obj === "string" || typeof obj === "number" || typeof obj === "boolean") return obj;
@@ -88,104 +83,55 @@
     return obj;
   }
 
+  const pending = new Map();
+  let nextId = 1;
+
   /**
-   * Restore JSON-safe objects back into native JS objects (async).
-   * Converts {media_type, bytes} into a usable data: URL.
+   * Calls `cmd` with `args` (an object of named arguments or an array of
+   * positional ones). Rust APIs are answered by the event loop directly,
+   * anything else by the Python `@command` of that name.
+   * `options.label` targets another window than the calling one.
    */
-  async function jsonRestoreObjectAsync(obj) {
-    if (obj === null || obj === undefined) return null;
-
-    if (typeof obj === "string") {
-      if (/^data:.*;base64,/.test(obj)) {
-        return obj;
-      }
-      return obj;
-    }
-
-    if (Array.isArray(obj)) {
-      return Promise.all(obj.map((v) => jsonRestoreObjectAsync(v)));
-    }
-
-    if (typeof obj === "object" && obj.constructor === Object) {
-      // 🔑 Media object detected
-      if (obj.media_type && obj.bytes) {
-        return `data:${obj.media_type};base64,${obj.bytes}`;
-      }
-
-      const restored = {};
-      for (const [k, v] of Object.entries(obj)) {
-        restored[k] = await jsonRestoreObjectAsync(v);
-      }
-      return restored;
-    }
-
-    return obj;
-  }
-
-  function transformCallback(callback, once) {
-    const identifier = uid();
-    const prop = `_${identifier}`;
-    const isAsync = callback && callback.constructor && callback.constructor.name === "AsyncFunction";
-
-    Object.defineProperty(window, prop, {
-      value: async (result) => {
-        if (once) Reflect.deleteProperty(window, prop);
-        if (!callback) return;
-
-        if (isAsync) {
-          const restored = await jsonRestoreObjectAsync(result);
-          return callback(restored);
-        } else {
-          const restored = jsonRestoreObjectSync(result);
-          return callback(restored);
-        }
-      },
-      writable: false,
-      configurable: true,
-    });
-
-    return identifier;
-  }
-
-  function invoke(cmd, args) {
+  function invoke(cmd, args, options) {
     return new Promise((resolve, reject) => {
-      if (!PyOrionConnections.is_connected()) {
-        reject(new Error("Socket is not connected or unavailable!"));
-        return;
-      }
-
-      const py_args = jsonMakeObjectSafe(args ?? {});
-      const result_id = transformCallback((result) => resolve(result), true);
-      const error_id = transformCallback((error) => reject(error), true);
-
+      const id = nextId++;
       const message = {
+        id,
         cmd,
-        result_id,
-        error_id,
-        payload: py_args,
+        args: jsonMakeObjectSafe(args ?? {}),
       };
-
-      PyOrionConnections.send(message);
+      if (options && options.label) message.label = options.label;
+
+      pending.set(id, { resolve, reject });
+      try {
+        window.ipc.postMessage(JSON.stringify(message));
+      } catch (err) {
+        pending.delete(id);
+        reject(err);
+      }
     });
   }
 
-  PyOrionConnections.on("message", (raw) => {
-    try {
-      const data = jsonMakeObjectSafe(JSON.parse(raw));
-      const { result_id, error_id, result, error } = data;
-
-      if (result_id) {
-        const prop = `_${result_id}`;
-        if (window[prop]) window[prop](result);
-      }
-
-      if (error_id) {
-        const prop = `_${error_id}`;
-        if (window[prop]) window[prop](error);
-      }
-    } catch (err) {
-      console.warn("Invalid message received:", raw);
+  /** Settles an `invoke` with its `[id, code, msg, result]` response. */
+  function resolveResponse([id, code, msg, result]) {
+    const call = pending.get(id);
+    if (!call) return;
+    pending.delete(id);
+
+    if (code === 0) {
+      call.resolve(jsonRestoreObjectSync(result));
+    } else {
+      const error = new Error(msg);
+      error.code = code;
+      error.details = result;
+      call.reject(error);
     }
+  }
+
+  Object.defineProperty(window, "__PYORION_IPC__", {
+    value: Object.freeze({ resolve: resolveResponse }),
+    writable: false,
+    configurable: false,
   });
 
   window.invoke = invoke;
@@ -5,9 +5,14 @@
 use serde::Deserialize;
 use serialize_to_javascript::{default_template, Template};
 
+pub mod protocol;
+
 #[derive(Deserialize, Template, Debug, Clone)]
 #[default_template("pyorion_socket.js")]
 pub struct WebSocketConfig {
+    /// Where pages connect. With `embedded` only its path is kept; the
+    /// host and port are those the frame process bound.
+    #[serde(default)]
     url: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -18,6 +23,31 @@ pub struct WebSocketConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     reconnect_interval: Option<u64>,
+
+    /// Serve the socket from the frame process, see
+    /// [`websocket`](crate::connections::websocket).
+    #[serde(default)]
+    embedded: bool,
+
+    /// Secret of this launch that pages send as `token` query parameter;
+    /// only set by [`bind`](crate::connections::websocket::bind).
+    #[serde(skip_deserializing)]
+    token: Option<String>,
+}
+
+impl WebSocketConfig {
+    pub fn embedded(&self) -> bool {
+        self.embedded
+    }
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    pub fn set_url(&mut self, url: String) {
+        self.url = url;
+    }
+    pub fn set_token(&mut self, token: String) {
+        self.token = Some(token);
+    }
 }
 
 pub fn websocket_config(cfg: WebSocketConfig) -> anyhow::Result<String> {
@@ -29,3 +59,4 @@ pub fn websocket_config(cfg: WebSocketConfig) -> anyhow::Result<String> {
 }
 
 pub static _COMMAND_SCRIPT: &str = include_str!("./invoke.js");
+pub static _EVENTS_SCRIPT: &str = include_str!("./events.js");
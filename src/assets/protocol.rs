@@ -0,0 +1,480 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Files of the `wry://` protocol, confined to the asset folder.
+//!
+//! Every request path is percent-decoded segment by segment and each
+//! segment has to be a plain file name, so neither `..` nor an encoded
+//! separator (`%2F`, `%5C`) nor a drive prefix can walk out of the folder.
+//! What is left is resolved against the canonical root and, depending on
+//! the [`SymlinkPolicy`], has to stay inside it after symlinks are
+//! followed. Refused paths are answered with `403`, missing files with
+//! `404`.
+//!
+//! Files are served with `Accept-Ranges`, so media elements can seek: a
+//! single `Range` is answered with `206 Partial Content` and only that
+//! window of the file is read, at most [`MAX_RANGE_LEN`] bytes of it.
+//! Without `Range` the whole file is read into the response, so files
+//! larger than [`MAX_FILE_LEN`] are refused with `413 Content Too Large`;
+//! media elements ask for ranges and are not affected. `HEAD` gets the same
+//! headers without a body.
+//!
+//! [`respond`] reads from disk, so the protocol handler calls it off the
+//! event loop.
+
+use pyorion_options::window::SymlinkPolicy;
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Component, Path, PathBuf},
+};
+use wry::http::{
+    header::{ACCEPT_RANGES, ALLOW, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE},
+    Method, Request, Response, StatusCode,
+};
+
+use crate::utils::MimeType;
+
+/// Largest window one range response reads; players ask for the rest.
+pub const MAX_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// Largest file served without a `Range` header.
+pub const MAX_FILE_LEN: u64 = 64 * 1024 * 1024;
+
+/// Why a request path is not served.
+#[derive(Debug)]
+pub enum AssetError {
+    /// The path leaves the root or runs through a refused symlink.
+    Forbidden,
+    NotFound,
+    /// Asked for whole, but larger than [`MAX_FILE_LEN`].
+    TooLarge,
+    Io(io::Error),
+}
+
+impl AssetError {
+    fn status(&self) -> StatusCode {
+        match self {
+            AssetError::Forbidden => StatusCode::FORBIDDEN,
+            AssetError::NotFound => StatusCode::NOT_FOUND,
+            AssetError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            AssetError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<io::Error> for AssetError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound => AssetError::NotFound,
+            io::ErrorKind::PermissionDenied => AssetError::Forbidden,
+            _ => AssetError::Io(e),
+        }
+    }
+}
+
+/// Maps the raw (still percent-encoded) request path to a file inside
+/// `root`; `/` is the `index` page.
+pub fn resolve(
+    root: &Path,
+    path: &str,
+    index: &str,
+    policy: SymlinkPolicy,
+) -> Result<PathBuf, AssetError> {
+    let mut relative = PathBuf::new();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let segment = decode(segment).ok_or(AssetError::Forbidden)?;
+        let mut components = Path::new(&segment).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(name)), None) if !segment.contains(['/', '\\']) => {
+                relative.push(name)
+            }
+            _ => return Err(AssetError::Forbidden),
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push(index);
+    }
+
+    let root = root.canonicalize()?;
+    if policy == SymlinkPolicy::Deny {
+        let mut current = root.clone();
+        for part in relative.components() {
+            current.push(part);
+            if current.symlink_metadata()?.file_type().is_symlink() {
+                return Err(AssetError::Forbidden);
+            }
+        }
+    }
+    let file = root.join(&relative).canonicalize()?;
+    if policy != SymlinkPolicy::Follow && !file.starts_with(&root) {
+        return Err(AssetError::Forbidden);
+    }
+    if !file.is_file() {
+        return Err(AssetError::NotFound);
+    }
+    Ok(file)
+}
+
+/// Answers one request of the protocol.
+pub fn respond(
+    request: &Request<Vec<u8>>,
+    root: &str,
+    index: &str,
+    policy: SymlinkPolicy,
+) -> Response<Vec<u8>> {
+    let head = request.method() == Method::HEAD;
+    if !head && request.method() != Method::GET {
+        return Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .header(ALLOW, "GET, HEAD")
+            .body(Vec::new())
+            .unwrap();
+    }
+    let range = request
+        .headers()
+        .get(RANGE)
+        .and_then(|range| range.to_str().ok());
+    let served = resolve(Path::new(root), request.uri().path(), index, policy)
+        .and_then(|file| serve_file(&file, range, head));
+    match served {
+        Ok(response) => response,
+        Err(e) => {
+            if let AssetError::Io(e) = &e {
+                eprintln!("[assets] Failed to serve {}: {}", request.uri().path(), e);
+            }
+            let status = e.status();
+            let reason = status.canonical_reason().unwrap_or_default();
+            Response::builder()
+                .status(status)
+                .header(CONTENT_TYPE, "text/plain")
+                .body(reason.as_bytes().to_vec())
+                // only fails for invalid header values, and ours are constant
+                .unwrap()
+        }
+    }
+}
+
+/// The part of a file a `Range` header asks for.
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Full,
+    /// First and last byte, inclusive.
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// Reads a `Range` header for a file of `len` bytes. Anything but a single
+/// `bytes` range is ignored, as RFC 9110 allows, and served in full.
+fn parse_range(header: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    let Some((first, last)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let (first, last) = (first.trim(), last.trim());
+    let (start, end) = if first.is_empty() {
+        // `-n` is the last n bytes
+        match last.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(n) if len > 0 => (len.saturating_sub(n), len - 1),
+            Ok(_) => return ByteRange::Unsatisfiable,
+            Err(_) => return ByteRange::Full,
+        }
+    } else {
+        let Ok(start) = first.parse::<u64>() else {
+            return ByteRange::Full;
+        };
+        let end = match last {
+            "" => u64::MAX,
+            last => match last.parse::<u64>() {
+                Ok(end) if end >= start => end,
+                _ => return ByteRange::Full,
+            },
+        };
+        if start >= len {
+            return ByteRange::Unsatisfiable;
+        }
+        (start, end.min(len - 1))
+    };
+    ByteRange::Partial(start, end.min(start + MAX_RANGE_LEN - 1))
+}
+
+/// Answers with the whole file or the window `range` asks for.
+fn serve_file(
+    file: &Path,
+    range: Option<&str>,
+    head: bool,
+) -> Result<Response<Vec<u8>>, AssetError> {
+    let mut content = File::open(file)?;
+    let len = content.metadata()?.len();
+    let builder = Response::builder()
+        .header(
+            CONTENT_TYPE,
+            MimeType::parse_from_uri(&file.to_string_lossy()).to_string(),
+        )
+        .header(ACCEPT_RANGES, "bytes");
+    let (builder, start, count) = match parse_range(range, len) {
+        ByteRange::Full if len > MAX_FILE_LEN => return Err(AssetError::TooLarge),
+        ByteRange::Full => (builder.status(StatusCode::OK), 0, len),
+        ByteRange::Partial(start, end) => (
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len)),
+            start,
+            end - start + 1,
+        ),
+        ByteRange::Unsatisfiable => {
+            let response = builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(CONTENT_RANGE, format!("bytes */{}", len))
+                .header(CONTENT_LENGTH, 0)
+                .body(Vec::new());
+            return Ok(response.unwrap());
+        }
+    };
+
+    let mut body = Vec::new();
+    if !head {
+        content.seek(SeekFrom::Start(start))?;
+        body.reserve(count as usize);
+        content.take(count).read_to_end(&mut body)?;
+    }
+    let response = builder.header(CONTENT_LENGTH, count).body(body);
+    Ok(response.unwrap())
+}
+
+/// Percent-decodes one path segment; `None` for malformed escapes, NUL
+/// bytes or invalid UTF-8.
+fn decode(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    if out.contains(&0) {
+        return None;
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, ops::Deref};
+
+    /// Directory of a test, removed when dropped.
+    struct Fixture(PathBuf);
+
+    impl Deref for Fixture {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// `<tmp>/<name>/{root/{index.html, js/app.js}, secret.txt}`.
+    fn fixture(name: &str) -> (Fixture, PathBuf) {
+        let base =
+            std::env::temp_dir().join(format!("pyorion-assets-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let root = base.join("root");
+        fs::create_dir_all(root.join("js")).unwrap();
+        fs::write(root.join("index.html"), "<html></html>").unwrap();
+        fs::write(root.join("js/app.js"), "app()").unwrap();
+        fs::write(base.join("secret.txt"), "secret").unwrap();
+        (Fixture(base), root)
+    }
+
+    fn status(root: &Path, path: &str, policy: SymlinkPolicy) -> u16 {
+        match resolve(root, path, "index.html", policy) {
+            Ok(_) => 200,
+            Err(e) => e.status().as_u16(),
+        }
+    }
+
+    #[test]
+    fn serves_files_inside_the_root() {
+        let (_base, root) = fixture("inside");
+        let policy = SymlinkPolicy::default();
+        let index = resolve(&root, "/", "index.html", policy).unwrap();
+        assert!(index.ends_with("index.html"));
+        assert_eq!(status(&root, "/js/app.js", policy), 200);
+        assert_eq!(status(&root, "/js/%61pp.js", policy), 200);
+        assert_eq!(status(&root, "//js//app.js", policy), 200);
+    }
+
+    #[test]
+    fn refuses_traversal() {
+        let (_base, root) = fixture("traversal");
+        let policy = SymlinkPolicy::default();
+        for path in [
+            "/../secret.txt",
+            "/js/../../secret.txt",
+            "/%2e%2e/secret.txt",
+            "/%2E%2E/secret.txt",
+            "/.%2e/secret.txt",
+            "/..%2Fsecret.txt",
+            "/..%2fsecret.txt",
+            "/..%5Csecret.txt",
+            "/%2F..%2Fsecret.txt",
+            "/%2Fetc%2Fpasswd",
+            "/./index.html",
+            "/index.html%00.js",
+            "/%zz",
+            "/%",
+            "/%ff",
+        ] {
+            assert_eq!(status(&root, path, policy), 403, "{}", path);
+        }
+    }
+
+    #[test]
+    fn missing_files_are_not_found() {
+        let (_base, root) = fixture("missing");
+        let policy = SymlinkPolicy::default();
+        assert_eq!(status(&root, "/nope.js", policy), 404);
+        assert_eq!(status(&root, "/js", policy), 404);
+        assert_eq!(status(&root.join("gone"), "/", policy), 404);
+    }
+
+    fn request(method: &str, path: &str, range: Option<&str>) -> Request<Vec<u8>> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("wry://localhost{}", path));
+        if let Some(range) = range {
+            request = request.header(RANGE, range);
+        }
+        request.body(Vec::new()).unwrap()
+    }
+
+    fn header<'a>(response: &'a Response<Vec<u8>>, name: &str) -> &'a str {
+        response.headers()[name].to_str().unwrap()
+    }
+
+    #[test]
+    fn parses_single_ranges() {
+        use ByteRange::*;
+        assert_eq!(parse_range(None, 10), Full);
+        assert_eq!(parse_range(Some("bytes=2-5"), 10), Partial(2, 5));
+        assert_eq!(parse_range(Some("bytes=2-"), 10), Partial(2, 9));
+        assert_eq!(parse_range(Some("bytes=2-99"), 10), Partial(2, 9));
+        assert_eq!(parse_range(Some("bytes=-3"), 10), Partial(7, 9));
+        assert_eq!(parse_range(Some("bytes=-30"), 10), Partial(0, 9));
+        assert_eq!(parse_range(Some("bytes=10-"), 10), Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=-0"), 10), Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-"), 0), Unsatisfiable);
+        // malformed or multiple ranges are ignored
+        assert_eq!(parse_range(Some("bytes=5-2"), 10), Full);
+        assert_eq!(parse_range(Some("bytes=0-1,4-5"), 10), Full);
+        assert_eq!(parse_range(Some("items=0-1"), 10), Full);
+        assert_eq!(parse_range(Some("bytes=a-b"), 10), Full);
+    }
+
+    #[test]
+    fn caps_the_window_of_a_range() {
+        let len = 10 * MAX_RANGE_LEN;
+        assert_eq!(
+            parse_range(Some("bytes=0-"), len),
+            ByteRange::Partial(0, MAX_RANGE_LEN - 1)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=5-"), len),
+            ByteRange::Partial(5, MAX_RANGE_LEN + 4)
+        );
+    }
+
+    #[test]
+    fn answers_ranges_and_head_requests() {
+        let (_base, root) = fixture("ranges");
+        fs::write(root.join("clip.mp4"), b"0123456789").unwrap();
+        let root = root.to_str().unwrap();
+        let serve = |request| respond(&request, root, "index.html", SymlinkPolicy::default());
+
+        let full = serve(request("GET", "/clip.mp4", None));
+        assert_eq!(full.status(), StatusCode::OK);
+        assert_eq!(header(&full, "accept-ranges"), "bytes");
+        assert_eq!(header(&full, "content-length"), "10");
+        assert_eq!(header(&full, "content-type"), "video/mp4");
+        assert_eq!(full.body(), b"0123456789");
+
+        let part = serve(request("GET", "/clip.mp4", Some("bytes=3-6")));
+        assert_eq!(part.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(header(&part, "content-range"), "bytes 3-6/10");
+        assert_eq!(header(&part, "content-length"), "4");
+        assert_eq!(part.body(), b"3456");
+
+        let tail = serve(request("GET", "/clip.mp4", Some("bytes=-2")));
+        assert_eq!(tail.body(), b"89");
+
+        let past = serve(request("GET", "/clip.mp4", Some("bytes=10-")));
+        assert_eq!(past.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(header(&past, "content-range"), "bytes */10");
+
+        let head = serve(request("HEAD", "/clip.mp4", Some("bytes=3-6")));
+        assert_eq!(head.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(header(&head, "content-length"), "4");
+        assert!(head.body().is_empty());
+
+        let post = serve(request("POST", "/clip.mp4", None));
+        assert_eq!(post.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(serve(request("GET", "/nope.mp4", None)).status(), 404);
+    }
+
+    #[test]
+    fn refuses_whole_files_above_the_cap() {
+        let (_base, root) = fixture("large");
+        let file = fs::File::create(root.join("clip.mp4")).unwrap();
+        // sparse, nothing is written
+        file.set_len(MAX_FILE_LEN + 1).unwrap();
+        let root = root.to_str().unwrap();
+        let serve = |request| respond(&request, root, "index.html", SymlinkPolicy::default());
+
+        let whole = serve(request("GET", "/clip.mp4", None));
+        assert_eq!(whole.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(header(&whole, "content-type"), "text/plain");
+
+        let part = serve(request("GET", "/clip.mp4", Some("bytes=0-")));
+        assert_eq!(part.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(part.body().len() as u64, MAX_RANGE_LEN);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn applies_the_symlink_policy() {
+        let (base, root) = fixture("symlinks");
+        std::os::unix::fs::symlink(base.join("secret.txt"), root.join("escape.txt")).unwrap();
+        std::os::unix::fs::symlink(root.join("js/app.js"), root.join("alias.js")).unwrap();
+
+        let contained = SymlinkPolicy::Contained;
+        assert_eq!(status(&root, "/escape.txt", contained), 403);
+        assert_eq!(status(&root, "/alias.js", contained), 200);
+
+        let follow = SymlinkPolicy::Follow;
+        assert_eq!(status(&root, "/escape.txt", follow), 200);
+
+        let deny = SymlinkPolicy::Deny;
+        assert_eq!(status(&root, "/alias.js", deny), 403);
+        assert_eq!(status(&root, "/js/app.js", deny), 200);
+    }
+}
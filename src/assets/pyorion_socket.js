@@ -90,10 +90,15 @@ function jsonMakeObjectSafe(obj) {
 
     shouldReconnect = config.autoReconnect;
 
+    var url = __TEMPLATE_url__;
+    if (__TEMPLATE_token__) {
+      url += (url.indexOf("?") < 0 ? "?" : "&") + "token=" + encodeURIComponent(__TEMPLATE_token__);
+    }
+
     if (config.protocols) {
-      ws = new WebSocket(__TEMPLATE_url__, config.protocols);
+      ws = new WebSocket(url, config.protocols);
     } else {
-      ws = new WebSocket(__TEMPLATE_url__);
+      ws = new WebSocket(url);
     }
 
     ws.onopen = function (e) { dispatchEvent('open', e); };
@@ -0,0 +1,420 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Raw bytes in API values.
+//!
+//! `serde_json::Value` has no byte type, so arguments and results travel as
+//! [`Payload`]s instead, MessagePack's data model. A [`Bytes`] result stays
+//! a byte buffer from the handler to the client: the MessagePack codec
+//! writes it as native binary, the JSON codec as a base64 string, as before.
+//! Incoming binary is kept the same way, while a [`Bytes`] argument of a
+//! JSON request may also be a base64 string or a list of bytes.
+//!
+//! Nothing in a value is rewritten on the way, so an object with a key
+//! like `"$bytes"` is ordinary data in either codec.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rmpv::Value as Raw;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer},
+        DeserializeOwned, Deserializer, IntoDeserializer, SeqAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{SerializeMap, SerializeSeq, Serializer},
+    Deserialize, Serialize,
+};
+use std::{borrow::Cow, fmt};
+
+/// Byte buffer for handler arguments and results, sent as binary by binary
+/// codecs and as base64 by JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // turned into base64 by `Payload` for JSON clients
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+// described the way JSON clients see it
+impl JsonSchema for Bytes {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "Bytes".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "type": "string", "contentEncoding": "base64" })
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("binary data, a base64 string or a list of bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                Ok(Bytes(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Bytes, E> {
+                STANDARD.decode(v).map(Bytes).map_err(E::custom)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bytes, A::Error> {
+                let mut bytes = Vec::with_capacity(preallocate(seq.size_hint()));
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Ok(Bytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(BytesVisitor)
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Bytes(bytes)
+    }
+}
+
+/// Capacity to reserve for a sequence. Binary formats announce lengths up
+/// front, so the hint comes from the peer and is capped.
+fn preallocate(hint: Option<usize>) -> usize {
+    hint.unwrap_or(0).min(4096)
+}
+
+/// An argument or result value. Holds anything JSON can, plus raw bytes.
+///
+/// Serializes as MessagePack binary for binary codecs and as a base64
+/// string for JSON. Typed values are read out of it with [`from_payload`],
+/// which follows serde_json's conventions, e.g. for enums.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Payload(pub Raw);
+
+impl Default for Payload {
+    fn default() -> Self {
+        Payload(Raw::Nil)
+    }
+}
+
+impl Payload {
+    /// The value as JSON, with bytes as base64 strings.
+    pub fn to_json(&self) -> serde_json::Value {
+        // only fails for map keys JSON has no form for
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl From<serde_json::Value> for Payload {
+    fn from(value: serde_json::Value) -> Self {
+        use serde_json::Value;
+
+        Payload(match value {
+            Value::Null => Raw::Nil,
+            Value::Bool(v) => Raw::Boolean(v),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(v), _) => Raw::from(v),
+                (_, Some(v)) => Raw::from(v),
+                _ => Raw::F64(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => Raw::String(s.into()),
+            Value::Array(items) => {
+                Raw::Array(items.into_iter().map(|v| Payload::from(v).0).collect())
+            }
+            Value::Object(map) => Raw::Map(
+                map.into_iter()
+                    .map(|(k, v)| (Raw::String(k.into()), Payload::from(v).0))
+                    .collect(),
+            ),
+        })
+    }
+}
+
+/// Converts `value` into a [`Payload`], encoding it the way the MessagePack
+/// codec does, so [`Bytes`] stay binary.
+pub fn to_payload<T: Serialize>(value: T) -> Result<Payload, rmp_serde::encode::Error> {
+    let buf = rmp_serde::to_vec_named(&value)?;
+    rmpv::decode::read_value(&mut buf.as_slice())
+        .map(Payload)
+        .map_err(|e| rmp_serde::encode::Error::Syntax(e.to_string()))
+}
+
+/// Reads a typed value out of a [`Payload`], like `serde_json::from_value`.
+pub fn from_payload<T: DeserializeOwned>(payload: Payload) -> Result<T, de::value::Error> {
+    T::deserialize(payload)
+}
+
+/// Writes a value for the target format.
+struct Wire<'a>(&'a Raw);
+
+impl Serialize for Wire<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Raw::Nil => serializer.serialize_unit(),
+            Raw::Boolean(v) => serializer.serialize_bool(*v),
+            Raw::Integer(n) => match n.as_u64() {
+                Some(v) => serializer.serialize_u64(v),
+                None => serializer.serialize_i64(n.as_i64().unwrap_or_default()),
+            },
+            Raw::F32(v) => serializer.serialize_f32(*v),
+            Raw::F64(v) => serializer.serialize_f64(*v),
+            Raw::String(s) => match s.as_str() {
+                Some(s) => serializer.serialize_str(s),
+                None => serializer.serialize_bytes(s.as_bytes()),
+            },
+            Raw::Binary(bytes) if serializer.is_human_readable() => {
+                serializer.serialize_str(&STANDARD.encode(bytes))
+            }
+            Raw::Binary(bytes) => serializer.serialize_bytes(bytes),
+            Raw::Array(items) => {
+                let mut out = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    out.serialize_element(&Wire(item))?;
+                }
+                out.end()
+            }
+            Raw::Map(entries) => {
+                let mut out = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    out.serialize_entry(&Wire(key), &Wire(value))?;
+                }
+                out.end()
+            }
+            ext @ Raw::Ext(..) => ext.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Wire(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Raw::deserialize(deserializer).map(Payload)
+    }
+}
+
+// any value, like `serde_json::Value`
+impl JsonSchema for Payload {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        serde_json::Value::schema_name()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        serde_json::Value::json_schema(generator)
+    }
+}
+
+impl<'de> Deserializer<'de> for Payload {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Raw::Nil => visitor.visit_unit(),
+            Raw::Boolean(v) => visitor.visit_bool(v),
+            Raw::Integer(n) => match n.as_u64() {
+                Some(v) => visitor.visit_u64(v),
+                None => visitor.visit_i64(n.as_i64().unwrap_or_default()),
+            },
+            Raw::F32(v) => visitor.visit_f32(v),
+            Raw::F64(v) => visitor.visit_f64(v),
+            Raw::String(s) if s.is_str() => visitor.visit_string(s.into_str().unwrap_or_default()),
+            Raw::String(s) => visitor.visit_byte_buf(s.into_bytes()),
+            Raw::Binary(bytes) => visitor.visit_byte_buf(bytes),
+            Raw::Array(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter().map(Payload));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Raw::Map(entries) => {
+                let mut map = MapDeserializer::new(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (Payload(key), Payload(value))),
+                );
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Raw::Ext(..) => Err(de::Error::invalid_type(
+                de::Unexpected::Other("MessagePack extension"),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Raw::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    // `"Variant"` for unit variants, `{"Variant": content}` for the others
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Raw::String(s) if s.is_str() => {
+                let variant: String = s.into_str().unwrap_or_default();
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            Raw::Map(entries) if entries.len() == 1 => {
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| (Payload(key), Payload(value)));
+                visitor.visit_enum(MapAccessDeserializer::new(MapDeserializer::new(entries)))
+            }
+            other => Payload(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, de::value::Error> for Payload {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bytes_results_read_as_base64_in_json() {
+        let image = std::collections::BTreeMap::from([("image", Bytes(vec![0, 1, 255]))]);
+        let value = to_payload(image).unwrap();
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"image":"AAH/"}"#
+        );
+        assert_eq!(value.to_json(), json!({ "image": "AAH/" }));
+    }
+
+    #[test]
+    fn bytes_travel_as_binary_in_msgpack() {
+        let value = to_payload((1, Bytes(vec![7; 3]))).unwrap();
+        assert_eq!(value.0[1], Raw::Binary(vec![7; 3]));
+        let frame = rmp_serde::to_vec_named(&value).unwrap();
+        // fixarray of 2, fixint 1, bin8 of length 3
+        assert_eq!(frame, vec![0x92, 0x01, 0xc4, 0x03, 7, 7, 7]);
+
+        let decoded: Payload = rmp_serde::from_slice(&frame).unwrap();
+        assert_eq!(decoded, value);
+        let (_, bytes): (u8, Bytes) = from_payload(decoded).unwrap();
+        assert_eq!(bytes.0, vec![7; 3]);
+    }
+
+    #[test]
+    fn bytes_arguments_accept_every_form() {
+        for arg in [json!("AAH/"), json!([0, 1, 255])] {
+            let bytes: Bytes = from_payload(arg.into()).unwrap();
+            assert_eq!(bytes.0, vec![0, 1, 255]);
+        }
+        let bytes: Bytes = from_payload(Payload(Raw::Binary(vec![0, 1, 255]))).unwrap();
+        assert_eq!(bytes.0, vec![0, 1, 255]);
+        assert!(from_payload::<Bytes>(json!({ "other": "AAH/" }).into()).is_err());
+        assert!(from_payload::<Bytes>(json!("not base64!").into()).is_err());
+    }
+
+    #[test]
+    fn a_bytes_key_is_plain_data() {
+        let data = json!({ "$bytes": "AAH/" });
+        let value = to_payload(&data).unwrap();
+        assert_eq!(serde_json::to_value(&value).unwrap(), data);
+
+        let frame = rmp_serde::to_vec_named(&value).unwrap();
+        let decoded: Payload = rmp_serde::from_slice(&frame).unwrap();
+        assert_eq!(from_payload::<serde_json::Value>(decoded).unwrap(), data);
+        assert!(from_payload::<Bytes>(data.into()).is_err());
+    }
+
+    #[test]
+    fn reads_typed_values_like_serde_json() {
+        #[derive(Deserialize, Serialize, Debug, PartialEq)]
+        enum Level {
+            Low,
+            Custom(u8),
+            Range { from: u8, to: u8 },
+        }
+
+        #[derive(Deserialize, Serialize, Debug, PartialEq)]
+        struct Options {
+            levels: Vec<Level>,
+            limit: Option<u32>,
+            ratio: f64,
+        }
+
+        let json = json!({
+            "levels": ["Low", { "Custom": 3 }, { "Range": { "from": 1, "to": 2 } }],
+            "ratio": 1,
+        });
+        let options = Options {
+            levels: vec![
+                Level::Low,
+                Level::Custom(3),
+                Level::Range { from: 1, to: 2 },
+            ],
+            limit: None,
+            ratio: 1.0,
+        };
+        assert_eq!(from_payload::<Options>(json.into()).unwrap(), options);
+        assert_eq!(
+            from_payload::<Options>(to_payload(&options).unwrap()).unwrap(),
+            options
+        );
+
+        let err = from_payload::<u32>(json!("x").into()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: string \"x\", expected u32");
+        assert!(from_payload::<(u32,)>(json!([1, 2]).into()).is_err());
+    }
+}
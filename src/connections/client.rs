@@ -0,0 +1,769 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Persistent, multiplexing IPC client exposed to Python.
+
+use pyo3::prelude::*;
+use serde::{de::IgnoredAny, Deserialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    sync::{mpsc, oneshot},
+};
+
+use super::codec::Codec;
+use super::framing::{read_frame, write_frame, FrameTooLarge, DEFAULT_MAX_FRAME_SIZE};
+use super::handler::STREAM_WINDOW;
+use super::handshake::PROTOCOL_VERSION;
+use crate::binary::{from_payload, Payload};
+use crate::error::code;
+use crate::utils::IdCounter;
+
+/// Extra time granted to the server to report a timeout itself before the
+/// client gives up locally.
+const TIMEOUT_GRACE: Duration = Duration::from_secs(1);
+
+/// Any duplex byte stream the client can talk over.
+pub trait IpcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> IpcStream for T {}
+
+/// Opens a connection to the IPC server called `name`.
+pub async fn connect(name: &str) -> std::io::Result<Box<dyn IpcStream>> {
+    #[cfg(windows)]
+    {
+        use tokio::net::windows::named_pipe::ClientOptions;
+        use tokio::time::sleep;
+        use windows_sys::Win32::Foundation::ERROR_PIPE_BUSY;
+
+        let pipe_full_name = super::endpoint::pipe_name(name)?;
+
+        loop {
+            match ClientOptions::new().open(&pipe_full_name) {
+                Ok(c) => return Ok(Box::new(c)),
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) => {}
+                Err(e) => return Err(e),
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        let path = super::endpoint::socket_path(name)?;
+        let stream = tokio::net::UnixStream::connect(&path).await?;
+        Ok(Box::new(stream))
+    }
+}
+
+/// Connects and, when a token is given, authenticates the connection and
+/// asks for `codec`. Returns the stream and the server info of the hello
+/// exchange, `null` without a token.
+pub async fn open_session(
+    name: &str,
+    token: Option<&str>,
+    protocol: u32,
+    codec: Codec,
+    max_frame_size: usize,
+) -> std::io::Result<(Box<dyn IpcStream>, serde_json::Value)> {
+    let mut stream = connect(name).await?;
+    let info = match token {
+        Some(token) => authenticate(&mut stream, token, protocol, codec, max_frame_size).await?,
+        None => serde_json::Value::Null,
+    };
+    Ok((stream, info))
+}
+
+/// Sends the hello frame every connection has to start with, see
+/// [`handshake`](super::handshake), and returns the server info once the
+/// server accepted it. The answer may be at most `max_frame_size` bytes.
+pub async fn authenticate<S>(
+    stream: &mut S,
+    token: &str,
+    protocol: u32,
+    codec: Codec,
+    max_frame_size: usize,
+) -> std::io::Result<serde_json::Value>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let hello = serde_json::json!({
+        "auth": token,
+        "protocol": protocol,
+        "version": crate::get_pyorion_version(),
+        "codec": codec.name(),
+    });
+    write_frame(stream, &serde_json::to_vec(&hello)?).await?;
+    let frame = read_frame(stream, max_frame_size).await?.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "connection closed during authentication",
+        )
+    })?;
+    let (_, code, msg, info): (u64, i32, String, serde_json::Value) =
+        serde_json::from_slice(&frame)?;
+    match code {
+        0 => Ok(info),
+        code::PROTOCOL => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, msg)),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            msg,
+        )),
+    }
+}
+
+// Only the id and code are needed to route a response frame to its caller.
+#[derive(Deserialize)]
+struct ResponseHead(u64, i32, IgnoredAny, IgnoredAny);
+
+/// `(id, code)` of a response frame.
+fn response_head(codec: Codec, frame: &[u8]) -> Option<(u64, i32)> {
+    let head = match codec {
+        Codec::Json => serde_json::from_slice(frame).ok(),
+        Codec::MsgPack => rmp_serde::from_slice(frame).ok(),
+    };
+    head.map(|ResponseHead(id, code, ..)| (id, code))
+}
+
+/// Converts a response frame for Python. Binary codecs hand byte buffers
+/// over as `bytes`, JSON as the base64 strings it carries.
+fn frame_to_py(py: Python<'_>, codec: Codec, frame: &[u8]) -> PyResult<Py<PyAny>> {
+    let value = codec
+        .decode(frame)
+        .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    super::utils::payload_to_py(py, &value)
+}
+
+/// A pushed `{"event", "window", "payload"}` frame.
+#[derive(Deserialize)]
+struct EventFrame {
+    event: String,
+    window: Option<String>,
+    payload: Payload,
+}
+
+/// Python callable invoked as `callback(event, window, payload)`.
+type EventCallback = Arc<Mutex<Option<Py<PyAny>>>>;
+
+fn dispatch_event(callback: &EventCallback, codec: Codec, frame: &[u8]) {
+    let Some(EventFrame {
+        event,
+        window,
+        payload,
+    }) = codec
+        .decode(frame)
+        .ok()
+        .and_then(|value| from_payload(value).ok())
+    else {
+        eprintln!("[client] Ignoring malformed event frame");
+        return;
+    };
+    Python::with_gil(|py| {
+        let Some(callback) = callback.lock().unwrap().as_ref().map(|c| c.clone_ref(py)) else {
+            return;
+        };
+        let payload = super::utils::payload_to_py(py, &payload);
+        let result = payload.and_then(|payload| callback.call1(py, (event, window, payload)));
+        if let Err(e) = result {
+            e.print(py);
+        }
+    });
+}
+
+enum CallError {
+    Timeout(u64, Duration),
+    Connection(String),
+}
+
+impl From<std::io::Error> for CallError {
+    fn from(e: std::io::Error) -> Self {
+        CallError::Connection(e.to_string())
+    }
+}
+
+impl From<CallError> for PyErr {
+    fn from(e: CallError) -> Self {
+        match e {
+            CallError::Timeout(id, timeout) => pyo3::exceptions::PyTimeoutError::new_err(format!(
+                "Request {} timed out after {:?}",
+                id, timeout
+            )),
+            CallError::Connection(msg) => pyo3::exceptions::PyConnectionError::new_err(msg),
+        }
+    }
+}
+
+/// Where the frames for one request id go.
+enum Waiter {
+    Once(oneshot::Sender<Vec<u8>>),
+    /// Gets every chunk and then the final response. Bounded, so the
+    /// reader waits while Python is behind, see [`IpcClient::stream`].
+    Stream(mpsc::Sender<Vec<u8>>),
+}
+
+impl Waiter {
+    /// Hands over the final response.
+    async fn finish(self, frame: Vec<u8>) {
+        // the caller may have given up already
+        match self {
+            Waiter::Once(tx) => {
+                let _ = tx.send(frame);
+            }
+            Waiter::Stream(tx) => {
+                let _ = tx.send(frame).await;
+            }
+        }
+    }
+}
+
+type Waiters = Arc<Mutex<HashMap<u64, Waiter>>>;
+
+/// One persistent connection with a writer and a reader task.
+struct Connection {
+    out_tx: mpsc::UnboundedSender<Vec<u8>>,
+    waiters: Waiters,
+    alive: Arc<AtomicBool>,
+    /// What the server reported in the hello exchange.
+    info: serde_json::Value,
+}
+
+impl Connection {
+    /// Opens a connection; pushed events are handed to `events` when given.
+    /// Frames larger than `max_frame_size` close it.
+    async fn open(
+        name: &str,
+        token: Option<&str>,
+        protocol: u32,
+        codec: Codec,
+        max_frame_size: usize,
+        events: Option<EventCallback>,
+    ) -> std::io::Result<Arc<Self>> {
+        let (stream, info) = open_session(name, token, protocol, codec, max_frame_size).await?;
+        let (mut reader, mut writer) = tokio::io::split(stream);
+        let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        let waiters: Waiters = Arc::new(Mutex::new(HashMap::new()));
+        let alive = Arc::new(AtomicBool::new(true));
+
+        let writer_alive = alive.clone();
+        tokio::spawn(async move {
+            while let Some(frame) = out_rx.recv().await {
+                if write_frame(&mut writer, &frame).await.is_err() {
+                    break;
+                }
+            }
+            writer_alive.store(false, Ordering::SeqCst);
+            // lets the server close its side, which in turn ends the reader
+            let _ = writer.shutdown().await;
+        });
+
+        let reader_alive = alive.clone();
+        let reader_waiters = waiters.clone();
+        tokio::spawn(async move {
+            loop {
+                let frame = match read_frame(&mut reader, max_frame_size).await {
+                    Ok(Some(frame)) => frame,
+                    Ok(None) => break,
+                    Err(e) => {
+                        if let Some(too_large) = FrameTooLarge::find(&e) {
+                            eprintln!("[client] Closing connection: {}", too_large);
+                        }
+                        break;
+                    }
+                };
+                // responses are arrays, pushed events are objects
+                if codec.is_object(&frame) {
+                    if let Some(events) = &events {
+                        dispatch_event(events, codec, &frame);
+                    }
+                    continue;
+                }
+                let Some((id, code)) = response_head(codec, &frame) else {
+                    eprintln!("[client] Ignoring malformed response frame");
+                    continue;
+                };
+                if code == code::CHUNK {
+                    let stream = match reader_waiters.lock().unwrap().get(&id) {
+                        Some(Waiter::Stream(tx)) => Some(tx.clone()),
+                        _ => None,
+                    };
+                    // not reading on while the stream is full keeps the
+                    // server's window closed until Python caught up
+                    if let Some(tx) = stream {
+                        let _ = tx.send(frame).await;
+                    }
+                    continue;
+                }
+                let waiter = reader_waiters.lock().unwrap().remove(&id);
+                if let Some(waiter) = waiter {
+                    waiter.finish(frame).await;
+                }
+            }
+            reader_alive.store(false, Ordering::SeqCst);
+            // dropping the senders fails every call still waiting here
+            reader_waiters.lock().unwrap().clear();
+        });
+
+        Ok(Arc::new(Self {
+            out_tx,
+            waiters,
+            alive,
+            info,
+        }))
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst) && !self.out_tx.is_closed()
+    }
+}
+
+struct ClientInner {
+    name: String,
+    token: Option<String>,
+    protocol: u32,
+    codec: Codec,
+    max_frame_size: usize,
+    ids: IdCounter,
+    next_slot: AtomicUsize,
+    slots: Vec<tokio::sync::Mutex<Option<Arc<Connection>>>>,
+    // subscriptions live on their own connection so the pool stays free
+    // to be rotated and reconnected
+    event_conn: tokio::sync::Mutex<Option<Arc<Connection>>>,
+    event_callback: EventCallback,
+    topics: Mutex<BTreeSet<String>>,
+}
+
+impl ClientInner {
+    /// Picks the next pool slot and (re)connects it when needed.
+    async fn connection(&self) -> std::io::Result<Arc<Connection>> {
+        let index = self.next_slot.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+        let mut slot = self.slots[index].lock().await;
+        if let Some(conn) = slot.as_ref() {
+            if conn.is_alive() {
+                return Ok(conn.clone());
+            }
+        }
+        let conn = Connection::open(
+            &self.name,
+            self.token.as_deref(),
+            self.protocol,
+            self.codec,
+            self.max_frame_size,
+            None,
+        )
+        .await?;
+        *slot = Some(conn.clone());
+        Ok(conn)
+    }
+
+    /// Returns the event connection, re-subscribing all topics after a
+    /// reconnect.
+    async fn event_connection(&self) -> Result<Arc<Connection>, CallError> {
+        let mut slot = self.event_conn.lock().await;
+        if let Some(conn) = slot.as_ref() {
+            if conn.is_alive() {
+                return Ok(conn.clone());
+            }
+        }
+        let conn = Connection::open(
+            &self.name,
+            self.token.as_deref(),
+            self.protocol,
+            self.codec,
+            self.max_frame_size,
+            Some(self.event_callback.clone()),
+        )
+        .await?;
+        let topics: Vec<String> = self.topics.lock().unwrap().iter().cloned().collect();
+        if !topics.is_empty() {
+            self.request(
+                &conn,
+                "event.subscribe".into(),
+                serde_json::json!([topics]),
+                None,
+                None,
+            )
+            .await?;
+        }
+        *slot = Some(conn.clone());
+        Ok(conn)
+    }
+
+    async fn call(
+        &self,
+        method: String,
+        args: serde_json::Value,
+        timeout: Option<Duration>,
+        label: Option<String>,
+    ) -> Result<Vec<u8>, CallError> {
+        let conn = self.connection().await?;
+        self.request(&conn, method, args, timeout, label).await
+    }
+
+    async fn request(
+        &self,
+        conn: &Connection,
+        method: String,
+        args: serde_json::Value,
+        timeout: Option<Duration>,
+        label: Option<String>,
+    ) -> Result<Vec<u8>, CallError> {
+        let (tx, rx) = oneshot::channel();
+        let id = self.send(conn, method, args, timeout, label, Waiter::Once(tx))?;
+
+        let response = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout + TIMEOUT_GRACE, rx).await {
+                Ok(response) => response,
+                Err(_) => {
+                    self.cancel(conn, id);
+                    return Err(CallError::Timeout(id, timeout));
+                }
+            },
+            None => rx.await,
+        };
+        response.map_err(|_| CallError::Connection("Connection lost before response".into()))
+    }
+
+    /// Registers `waiter` and queues the request, returning its id.
+    fn send(
+        &self,
+        conn: &Connection,
+        method: String,
+        args: serde_json::Value,
+        timeout: Option<Duration>,
+        label: Option<String>,
+        waiter: Waiter,
+    ) -> Result<u64, CallError> {
+        let id = self.ids.next();
+        let mut request = vec![serde_json::json!(id), method.into(), args];
+        if label.is_some() || timeout.is_some() {
+            request.push(label.into());
+        }
+        if let Some(timeout) = timeout {
+            // the server enforces the deadline and answers with a timeout error
+            request.push(serde_json::json!(timeout.as_millis() as u64));
+        }
+        let frame = self
+            .codec
+            .encode(&request)
+            .map_err(|e| CallError::Connection(e.to_string()))?;
+
+        conn.waiters.lock().unwrap().insert(id, waiter);
+        if conn.out_tx.send(frame).is_err() {
+            conn.waiters.lock().unwrap().remove(&id);
+            return Err(CallError::Connection(format!(
+                "Connection to {} is closed",
+                self.name
+            )));
+        }
+        Ok(id)
+    }
+
+    /// Gives up on request `id` and asks the server to stop it.
+    fn cancel(&self, conn: &Connection, id: u64) {
+        conn.waiters.lock().unwrap().remove(&id);
+        // best effort, nobody waits for the answer
+        let cancel = serde_json::json!([self.ids.next(), "request.cancel", [id]]);
+        if let Ok(frame) = self.codec.encode(&cancel) {
+            let _ = conn.out_tx.send(frame);
+        }
+    }
+}
+
+/// Frames of a streamed response, see [`IpcClient::stream`].
+///
+/// An async iterator over raw `[id, code, msg, result]` frames: chunks with
+/// code `100`, then the final response. Dropping it before the end cancels
+/// the request.
+#[pyclass(module = "pyorion._pyorion")]
+pub struct ResponseStream {
+    inner: Arc<ClientInner>,
+    conn: Arc<Connection>,
+    id: u64,
+    frames: Arc<tokio::sync::Mutex<mpsc::Receiver<Vec<u8>>>>,
+    timeout: Option<Duration>,
+    done: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl ResponseStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let inner = self.inner.clone();
+        let conn = self.conn.clone();
+        let id = self.id;
+        let frames = self.frames.clone();
+        let timeout = self.timeout;
+        let done = self.done.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            if done.load(Ordering::SeqCst) {
+                return Err(pyo3::exceptions::PyStopAsyncIteration::new_err(()));
+            }
+            let mut frames = frames.lock().await;
+            // the server restarts its timeout with every chunk, so this
+            // bounds the wait for the next frame only
+            let frame = match timeout {
+                Some(timeout) => {
+                    match tokio::time::timeout(timeout + TIMEOUT_GRACE, frames.recv()).await {
+                        Ok(frame) => frame,
+                        Err(_) => {
+                            done.store(true, Ordering::SeqCst);
+                            inner.cancel(&conn, id);
+                            // frees the reader if it waits for room here
+                            frames.close();
+                            return Err(CallError::Timeout(id, timeout).into());
+                        }
+                    }
+                }
+                None => frames.recv().await,
+            };
+            let Some(frame) = frame else {
+                done.store(true, Ordering::SeqCst);
+                return Err(CallError::Connection("Connection lost before response".into()).into());
+            };
+            if !matches!(response_head(inner.codec, &frame), Some((_, code::CHUNK))) {
+                done.store(true, Ordering::SeqCst);
+            }
+            Python::with_gil(|py| frame_to_py(py, inner.codec, &frame))
+        })
+    }
+}
+
+impl Drop for ResponseStream {
+    fn drop(&mut self) {
+        if !self.done.load(Ordering::SeqCst) {
+            self.inner.cancel(&self.conn, self.id);
+        }
+    }
+}
+
+/// Persistent client for the frame process' IPC server.
+///
+/// Holds a small pool of long-lived connections, multiplexes concurrent
+/// calls over them by request id and transparently reconnects a pool slot
+/// once its connection dropped. Every connection presents `token`, the
+/// session token the server was started with, and `protocol`, the wire
+/// protocol version the caller expects; the server refuses connections
+/// speaking another version. With `codec="msgpack"` frames are MessagePack
+/// and byte buffers in results arrive as `bytes`. A frame larger than
+/// `max_frame_size` bytes closes its connection and fails the calls waiting
+/// on it.
+#[pyclass(module = "pyorion._pyorion")]
+pub struct IpcClient {
+    inner: Arc<ClientInner>,
+}
+
+#[pymethods]
+impl IpcClient {
+    #[new]
+    #[pyo3(signature = (
+        name,
+        pool_size = 1,
+        token = None,
+        protocol = PROTOCOL_VERSION,
+        codec = "json",
+        max_frame_size = DEFAULT_MAX_FRAME_SIZE,
+    ))]
+    fn new(
+        name: String,
+        pool_size: usize,
+        token: Option<String>,
+        protocol: u32,
+        codec: &str,
+        max_frame_size: usize,
+    ) -> PyResult<Self> {
+        let codec = Codec::from_name(codec).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown codec {:?}, expected one of {:?}",
+                codec,
+                super::codec::CODECS
+            ))
+        })?;
+        let slots = (0..pool_size.max(1))
+            .map(|_| tokio::sync::Mutex::new(None))
+            .collect();
+        Ok(Self {
+            inner: Arc::new(ClientInner {
+                name,
+                token,
+                protocol,
+                codec,
+                max_frame_size,
+                ids: IdCounter::new(),
+                next_slot: AtomicUsize::new(0),
+                slots,
+                event_conn: tokio::sync::Mutex::new(None),
+                event_callback: Arc::new(Mutex::new(None)),
+                topics: Mutex::new(BTreeSet::new()),
+            }),
+        })
+    }
+
+    /// Sends `[id, method, args, label?]` and resolves with the raw
+    /// `[id, code, msg, result]` response.
+    ///
+    /// `args` is a JSON encoded argument list, `timeout` is in seconds.
+    #[pyo3(signature = (method, args = None, timeout = None, label = None))]
+    fn call<'py>(
+        &self,
+        py: Python<'py>,
+        method: String,
+        args: Option<String>,
+        timeout: Option<f64>,
+        label: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let args = match args {
+            Some(args) => serde_json::from_str(&args)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?,
+            None => serde_json::json!([]),
+        };
+        let timeout = timeout.map(Duration::from_secs_f64);
+        let inner = self.inner.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let resp = inner.call(method, args, timeout, label).await?;
+            Python::with_gil(|py| frame_to_py(py, inner.codec, &resp))
+        })
+    }
+
+    /// Sends a request to a streaming handler and resolves with a
+    /// [`ResponseStream`] over its frames.
+    ///
+    /// Arguments are as for [`call`](IpcClient::call); `timeout` bounds the
+    /// wait for each frame instead of the whole stream. At most
+    /// `STREAM_WINDOW` chunks wait for `__anext__`; beyond that the
+    /// connection stops reading, so the server holds the handler back
+    /// instead of the client buffering its whole result.
+    #[pyo3(signature = (method, args = None, timeout = None, label = None))]
+    fn stream<'py>(
+        &self,
+        py: Python<'py>,
+        method: String,
+        args: Option<String>,
+        timeout: Option<f64>,
+        label: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let args = match args {
+            Some(args) => serde_json::from_str(&args)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?,
+            None => serde_json::json!([]),
+        };
+        let timeout = timeout.map(Duration::from_secs_f64);
+        let inner = self.inner.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let conn = inner.connection().await.map_err(CallError::from)?;
+            let (tx, rx) = mpsc::channel(STREAM_WINDOW);
+            let id = inner.send(&conn, method, args, timeout, label, Waiter::Stream(tx))?;
+            Ok(ResponseStream {
+                inner,
+                conn,
+                id,
+                frames: Arc::new(tokio::sync::Mutex::new(rx)),
+                timeout,
+                done: Arc::new(AtomicBool::new(false)),
+            })
+        })
+    }
+
+    /// Resolves with what the server reported in the hello exchange:
+    /// `{"protocol", "version", "namespaces", "features"}`. Connects when no
+    /// connection is open yet.
+    fn server_info<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let inner = self.inner.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let conn = inner.connection().await.map_err(CallError::from)?;
+            let info = conn.info.to_string();
+            Python::with_gil(|py| super::utils::json_to_py(py, &info))
+        })
+    }
+
+    /// Subscribes to event topics; `"*"` subscribes to all of them.
+    ///
+    /// `callback(event, window, payload)` is invoked from a runtime thread
+    /// for every pushed event and replaces any previous callback. Resolves
+    /// with the raw response carrying the subscribed topics.
+    fn subscribe<'py>(
+        &self,
+        py: Python<'py>,
+        topics: Vec<String>,
+        callback: Py<PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        *self.inner.event_callback.lock().unwrap() = Some(callback);
+        self.inner
+            .topics
+            .lock()
+            .unwrap()
+            .extend(topics.iter().cloned());
+        let inner = self.inner.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let conn = inner.event_connection().await?;
+            let resp = inner
+                .request(
+                    &conn,
+                    "event.subscribe".into(),
+                    serde_json::json!([topics]),
+                    None,
+                    None,
+                )
+                .await?;
+            Python::with_gil(|py| frame_to_py(py, inner.codec, &resp))
+        })
+    }
+
+    /// Unsubscribes from the given topics, or from all when `None`.
+    #[pyo3(signature = (topics = None))]
+    fn unsubscribe<'py>(
+        &self,
+        py: Python<'py>,
+        topics: Option<Vec<String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let topics = topics.unwrap_or_default();
+        {
+            let mut current = self.inner.topics.lock().unwrap();
+            if topics.is_empty() {
+                current.clear();
+            } else {
+                current.retain(|t| !topics.contains(t));
+            }
+        }
+        let inner = self.inner.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let conn = inner.event_connection().await?;
+            let resp = inner
+                .request(
+                    &conn,
+                    "event.unsubscribe".into(),
+                    serde_json::json!([topics]),
+                    None,
+                    None,
+                )
+                .await?;
+            Python::with_gil(|py| frame_to_py(py, inner.codec, &resp))
+        })
+    }
+
+    /// Drops all pooled connections; the next call reconnects.
+    fn close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let inner = self.inner.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            for slot in &inner.slots {
+                slot.lock().await.take();
+            }
+            inner.event_conn.lock().await.take();
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Payload encodings of a native connection.
+//!
+//! The hello frame is always JSON. Its optional `"codec"` field selects the
+//! encoding of every later frame in both directions; JSON stays the default.
+//! MessagePack sends [`Bytes`](crate::binary::Bytes) as native binary
+//! instead of base64. Both are decoded into [`Payload`]s, so nothing behind
+//! the connection handler depends on the codec.
+
+use serde::Serialize;
+
+use crate::binary::Payload;
+
+/// Names accepted in the hello, reported back to clients.
+pub const CODECS: &[&str] = &["json", "msgpack"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Codec {
+    #[default]
+    Json,
+    MsgPack,
+}
+
+impl Codec {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Codec::Json),
+            "msgpack" => Some(Codec::MsgPack),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Json => "json",
+            Codec::MsgPack => "msgpack",
+        }
+    }
+
+    /// Encodes a frame. [`Payload`]s inside it are written for the codec.
+    pub fn encode<T: Serialize + ?Sized>(self, value: &T) -> std::io::Result<Vec<u8>> {
+        match self {
+            Codec::Json => Ok(serde_json::to_vec(value)?),
+            // structs as maps, so events keep their field names
+            Codec::MsgPack => rmp_serde::to_vec_named(value)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        }
+    }
+
+    /// Decodes a frame; the error describes what is wrong with it.
+    pub fn decode(self, buf: &[u8]) -> Result<Payload, String> {
+        match self {
+            Codec::Json => {
+                let text = std::str::from_utf8(buf)
+                    .map_err(|e| format!("frame is not valid UTF-8: {}", e))?;
+                serde_json::from_str(text).map_err(|e| format!("frame is not valid JSON: {}", e))
+            }
+            Codec::MsgPack => rmp_serde::from_slice(buf)
+                .map_err(|e| format!("frame is not valid MessagePack: {}", e)),
+        }
+    }
+
+    /// Whether the frame holds an object, which tells pushed events apart
+    /// from array responses without decoding them.
+    pub fn is_object(self, frame: &[u8]) -> bool {
+        match (self, frame.first()) {
+            (Codec::Json, Some(b'{')) => true,
+            // fixmap, map16, map32
+            (Codec::MsgPack, Some(0x80..=0x8f | 0xde | 0xdf)) => true,
+            _ => false,
+        }
+    }
+}
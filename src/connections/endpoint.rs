@@ -0,0 +1,117 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Where the IPC server listens and who owns the endpoint.
+//!
+//! On Unix the socket lives in `$XDG_RUNTIME_DIR` (private to the user),
+//! falling back to the temp dir, next to a `<name>.lock` file holding the
+//! owner's pid. A second server with the same name refuses to start while
+//! the owner still accepts connections and takes over a stale socket
+//! otherwise. On Windows the pipe name is the endpoint and
+//! `first_pipe_instance` gives the same guarantee.
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Files claimed by this process, removed by [`release_all`].
+static CLAIMED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// A lock younger than this belongs to a server that may still be binding.
+#[cfg(unix)]
+const STARTUP_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+
+fn check_name(name: &str) -> io::Result<()> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid endpoint name {:?}", name),
+        ));
+    }
+    Ok(())
+}
+
+/// Directory holding the sockets and their lock files.
+#[cfg(unix)]
+pub fn runtime_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Socket path of the endpoint `name`; used by the server and the client.
+#[cfg(unix)]
+pub fn socket_path(name: &str) -> io::Result<PathBuf> {
+    check_name(name)?;
+    Ok(runtime_dir().join(format!("{}.sock", name)))
+}
+
+/// Pipe path of the endpoint `name`; used by the server and the client.
+#[cfg(windows)]
+pub fn pipe_name(name: &str) -> io::Result<String> {
+    check_name(name)?;
+    Ok(format!(r"\\.\pipe\{}", name))
+}
+
+/// Claims the endpoint `name` for this process and returns the socket path
+/// to bind. Fails with `AddrInUse` while another server owns it.
+#[cfg(unix)]
+pub async fn claim(name: &str) -> io::Result<PathBuf> {
+    use std::fs;
+    use std::io::Write;
+
+    let socket = socket_path(name)?;
+    let lock = runtime_dir().join(format!("{}.lock", name));
+
+    // a second attempt only follows the removal of a stale lock
+    for _ in 0..2 {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock)
+        {
+            Ok(mut file) => {
+                writeln!(file, "{}", std::process::id())?;
+                // without a lock, a socket file is left over by a crash
+                let _ = fs::remove_file(&socket);
+                CLAIMED
+                    .lock()
+                    .unwrap()
+                    .extend([socket.clone(), lock.clone()]);
+                return Ok(socket);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                let starting = fs::metadata(&lock)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.elapsed().ok())
+                    .is_some_and(|age| age < STARTUP_GRACE);
+                let serving = tokio::net::UnixStream::connect(&socket).await.is_ok();
+                if starting || serving {
+                    let owner = fs::read_to_string(&lock).unwrap_or_default();
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("{} is owned by pid {}", socket.display(), owner.trim()),
+                    ));
+                }
+                // the owner is gone
+                let _ = fs::remove_file(&lock);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AddrInUse,
+        format!("could not claim {}", lock.display()),
+    ))
+}
+
+/// Removes the socket and lock files this process claimed. Called when the
+/// event loop shuts down.
+pub fn release_all() {
+    for path in CLAIMED.lock().unwrap().drain(..) {
+        let _ = std::fs::remove_file(path);
+    }
+}
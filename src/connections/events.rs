@@ -0,0 +1,188 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Push channel for unsolicited events.
+//!
+//! Clients subscribe to named topics with `event.subscribe` /
+//! `event.unsubscribe` requests, which are answered by the connection
+//! handler directly. Events are framed on the same connection as
+//! `{"event": topic, "window": label, "payload": ...}` objects, so they can
+//! never be mistaken for a `[id, code, msg, result]` response, in the codec
+//! the connection negotiated. JSON-RPC connections get them as `event`
+//! notifications instead.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::codec::Codec;
+use super::handler::Outgoing;
+use super::jsonrpc::{self, WireMode};
+use crate::{
+    api_manager::{ApiRequest, ApiResponse},
+    error::ApiError,
+    utils::ConnectionId,
+};
+
+/// Subscribing to this topic delivers every event.
+pub const WILDCARD: &str = "*";
+
+#[derive(Serialize)]
+struct EventMessage<'a> {
+    event: &'a str,
+    window: Option<&'a str>,
+    payload: &'a Value,
+}
+
+struct Subscriber {
+    out: UnboundedSender<Outgoing>,
+    mode: WireMode,
+    codec: Codec,
+    topics: HashSet<String>,
+}
+
+/// Routes events to the connections subscribed to them.
+#[derive(Clone, Default)]
+pub struct EventHub(Arc<Mutex<HashMap<ConnectionId, Subscriber>>>);
+
+impl EventHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes a connection reachable; it receives nothing until it subscribes.
+    pub fn attach(
+        &self,
+        conn_id: ConnectionId,
+        out: UnboundedSender<Outgoing>,
+        mode: WireMode,
+        codec: Codec,
+    ) {
+        self.0.lock().unwrap().insert(
+            conn_id,
+            Subscriber {
+                out,
+                mode,
+                codec,
+                topics: HashSet::new(),
+            },
+        );
+    }
+
+    pub fn detach(&self, conn_id: ConnectionId) {
+        self.0.lock().unwrap().remove(&conn_id);
+    }
+
+    /// Adds topics and returns the resulting subscription set.
+    pub fn subscribe(&self, conn_id: ConnectionId, topics: Vec<String>) -> Vec<String> {
+        let mut map = self.0.lock().unwrap();
+        match map.get_mut(&conn_id) {
+            Some(sub) => {
+                sub.topics.extend(topics);
+                sorted(&sub.topics)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Removes topics (all of them when `topics` is empty) and returns the
+    /// remaining subscription set.
+    pub fn unsubscribe(&self, conn_id: ConnectionId, topics: Vec<String>) -> Vec<String> {
+        let mut map = self.0.lock().unwrap();
+        match map.get_mut(&conn_id) {
+            Some(sub) => {
+                if topics.is_empty() {
+                    sub.topics.clear();
+                } else {
+                    sub.topics.retain(|t| !topics.contains(t));
+                }
+                sorted(&sub.topics)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Sends an event to every connection subscribed to `topic`.
+    pub fn emit(&self, topic: &str, window: Option<&str>, payload: Value) {
+        let mut map = self.0.lock().unwrap();
+        if map.is_empty() {
+            return;
+        }
+        let message = EventMessage {
+            event: topic,
+            window,
+            payload: &payload,
+        };
+        // every format is encoded once, for the first subscriber needing it
+        let mut frames: HashMap<(WireMode, Codec), Option<Vec<u8>>> = HashMap::new();
+        // a closed sender means the connection is going away
+        map.retain(|_, sub| {
+            if sub.topics.contains(topic) || sub.topics.contains(WILDCARD) {
+                let frame = frames
+                    .entry((sub.mode, sub.codec))
+                    .or_insert_with(|| match sub.mode {
+                        WireMode::Native => sub
+                            .codec
+                            .encode(&message)
+                            .map_err(|e| {
+                                eprintln!("[events] Failed to encode event {}: {:?}", topic, e)
+                            })
+                            .ok(),
+                        WireMode::JsonRpc => Some(jsonrpc::event_frame(topic, window, &payload)),
+                    });
+                match frame {
+                    Some(frame) => sub.out.send(frame.clone().into()).is_ok(),
+                    None => !sub.out.is_closed(),
+                }
+            } else {
+                !sub.out.is_closed()
+            }
+        });
+    }
+
+    /// Whether a live connection subscribed to `topic` by name; the
+    /// wildcard does not count.
+    pub fn has_subscribers(&self, topic: &str) -> bool {
+        let map = self.0.lock().unwrap();
+        map.values()
+            .any(|sub| sub.topics.contains(topic) && !sub.out.is_closed())
+    }
+
+    /// Answers the subscription control requests; `None` for any other method.
+    pub fn handle_control(&self, conn_id: ConnectionId, req: &ApiRequest) -> Option<ApiResponse> {
+        let subscribe = match req.1.as_str() {
+            "event.subscribe" => true,
+            "event.unsubscribe" => false,
+            _ => return None,
+        };
+        // either `[[topic, ...]]` or `[topic, ...]`
+        let topics = match req
+            .args()
+            .single::<Vec<String>>()
+            .or_else(|_| req.args().get::<Vec<String>>())
+        {
+            Ok(topics) => topics,
+            Err(e) => {
+                let err = ApiError::invalid_argument(0, "topics", e.to_string());
+                return Some(req.fail(&err));
+            }
+        };
+        let current = if subscribe {
+            self.subscribe(conn_id, topics)
+        } else {
+            self.unsubscribe(conn_id, topics)
+        };
+        Some(req.ok(current))
+    }
+}
+
+fn sorted(topics: &HashSet<String>) -> Vec<String> {
+    let mut topics: Vec<String> = topics.iter().cloned().collect();
+    topics.sort();
+    topics
+}
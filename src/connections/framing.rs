@@ -0,0 +1,186 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Length-prefixed framing shared by the IPC server and client.
+//!
+//! Every frame is a little-endian `u32` byte length followed by the payload.
+//! The reader checks the length against a limit before allocating, so a
+//! forged prefix cannot make it reserve gigabytes.
+
+use std::fmt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Frame limit used when the server config or the client does not set one.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// A frame announced more bytes than the reader accepts. Carried inside the
+/// `InvalidData` error returned by [`read_frame`].
+#[derive(Debug)]
+pub struct FrameTooLarge {
+    pub len: usize,
+    pub max: usize,
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame of {} bytes exceeds the limit of {} bytes",
+            self.len, self.max
+        )
+    }
+}
+
+impl std::error::Error for FrameTooLarge {}
+
+impl FrameTooLarge {
+    /// Finds the error inside an error returned by [`read_frame`].
+    pub fn find(err: &std::io::Error) -> Option<&FrameTooLarge> {
+        err.get_ref()?.downcast_ref::<FrameTooLarge>()
+    }
+}
+
+/// Reads one frame of at most `max_len` bytes. Returns `Ok(None)` when the
+/// peer closed the connection between two frames.
+///
+/// A larger frame fails with [`FrameTooLarge`] and leaves its payload
+/// unread, so the stream cannot be used afterwards.
+pub async fn read_frame<R>(reader: &mut R, max_len: usize) -> tokio::io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut len_buf = [0u8; 4];
+    match reader.read_exact(&mut len_buf).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_le_bytes(len_buf) as usize;
+    if len > max_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            FrameTooLarge { len, max: max_len },
+        ));
+    }
+
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).await?;
+    Ok(Some(buf))
+}
+
+/// Writes one frame and flushes it.
+pub async fn write_frame<W>(writer: &mut W, payload: &[u8]) -> tokio::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let len = u32::try_from(payload.len())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "frame too large"))?;
+    writer.write_all(&len.to_le_bytes()).await?;
+    writer.write_all(payload).await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small deterministic generator, enough to shake out edge cases.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        fn bytes(&mut self, len: usize) -> Vec<u8> {
+            (0..len).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    async fn encode(payloads: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for payload in payloads {
+            write_frame(&mut out, payload).await.unwrap();
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn round_trips_random_frames() {
+        let mut rng = Lcg(1);
+        let payloads: Vec<Vec<u8>> = (0..200)
+            .map(|_| {
+                let len = rng.next() as usize % 4096;
+                rng.bytes(len)
+            })
+            .collect();
+        let stream = encode(&payloads).await;
+
+        let mut reader = stream.as_slice();
+        for payload in &payloads {
+            let frame = read_frame(&mut reader, 4096).await.unwrap();
+            assert_eq!(frame.as_ref(), Some(payload));
+        }
+        assert!(read_frame(&mut reader, 4096).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_prefix_before_allocating() {
+        let mut stream = u32::MAX.to_le_bytes().to_vec();
+        stream.extend_from_slice(b"tail");
+
+        let err = read_frame(&mut stream.as_slice(), 1024).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let too_large = FrameTooLarge::find(&err).unwrap();
+        assert_eq!((too_large.len, too_large.max), (u32::MAX as usize, 1024));
+    }
+
+    #[tokio::test]
+    async fn accepts_frame_at_the_limit() {
+        let stream = encode(&[vec![7u8; 64]]).await;
+        let frame = read_frame(&mut stream.as_slice(), 64).await.unwrap();
+        assert_eq!(frame, Some(vec![7u8; 64]));
+
+        let err = read_frame(&mut stream.as_slice(), 63).await.unwrap_err();
+        assert!(FrameTooLarge::find(&err).is_some());
+    }
+
+    #[tokio::test]
+    async fn truncated_streams_never_yield_partial_frames() {
+        let payload = b"[1,\"window.title\",[]]".to_vec();
+        let stream = encode(std::slice::from_ref(&payload)).await;
+
+        for cut in 0..stream.len() {
+            let result = read_frame(&mut &stream[..cut], 1024).await;
+            match result {
+                // cut inside the length prefix reads as a closed connection
+                Ok(frame) => assert!(cut < 4 && frame.is_none(), "cut at {}", cut),
+                Err(e) => {
+                    assert!(cut >= 4, "cut at {}", cut);
+                    assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof);
+                }
+            }
+        }
+        let frame = read_frame(&mut stream.as_slice(), 1024).await.unwrap();
+        assert_eq!(frame, Some(payload));
+    }
+
+    #[tokio::test]
+    async fn random_garbage_never_panics() {
+        let mut rng = Lcg(42);
+        for _ in 0..2000 {
+            let len = rng.next() as usize % 64;
+            let garbage = rng.bytes(len);
+            let mut reader = garbage.as_slice();
+            // consume frames until the garbage runs out or turns invalid
+            while let Ok(Some(frame)) = read_frame(&mut reader, 256).await {
+                assert!(frame.len() <= 256);
+            }
+        }
+    }
+}
@@ -2,67 +2,846 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use futures_util::stream::{FuturesOrdered, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc::{self, unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
 
-pub async fn handle_client<S>(
+use super::codec::Codec;
+use super::framing::{read_frame, write_frame, FrameTooLarge};
+use super::handshake::{self, Hello};
+use super::jsonrpc::{self, Call, CallTable, WireMode};
+use super::ServerState;
+use crate::api_manager::{ApiRequest, ApiResponse, ChunkSink};
+use crate::binary::{from_payload, Payload};
+use crate::error::{code, ApiError};
+use crate::utils::{ConnectionId, IdCounter, PendingMap, UserEvent};
+
+/// Source of process-unique connection ids; request ids are scoped by them.
+static CONNECTION_IDS: IdCounter = IdCounter::new();
+
+/// How long a closing connection may take to write its queued responses.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a peer that stopped sending waits for the responses still being
+/// worked on.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Held by every task still working on a response of the connection; the
+/// channel closes once the last one is done, see [`handle_client`].
+type InFlight = mpsc::Sender<()>;
+
+/// How long a new connection may take to authenticate; also bounds the TLS
+/// handshake in front of it.
+pub(super) const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Limit for the authentication frame, far below the regular one since the
+/// peer is not trusted yet.
+const MAX_AUTH_FRAME_SIZE: usize = 4096;
+
+/// Chunks of one streamed response that may wait for the writer before the
+/// handler is held back.
+pub(super) const STREAM_WINDOW: usize = 8;
+
+/// A frame queued for the writer. Chunks of a streamed response hold a
+/// permit of their stream until they are written, which keeps a fast
+/// handler from queueing its whole result for a slow reader.
+pub struct Outgoing {
+    frame: Vec<u8>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl From<Vec<u8>> for Outgoing {
+    fn from(frame: Vec<u8>) -> Self {
+        Self {
+            frame,
+            _permit: None,
+        }
+    }
+}
+
+/// Serves one client connection.
+///
+/// Reading and writing run concurrently: every request is dispatched as soon
+/// as its frame arrives and its response is written whenever it completes,
+/// so a slow call never blocks the ones behind it. Responses carry the
+/// request id, which lets the client match them out of order. Pushed events
+/// share the same writer, as do the chunks of streamed results, which are
+/// sent ahead of their final response.
+///
+/// When the peer stops sending, the responses still being worked on go out
+/// as they complete, within [`DRAIN_TIMEOUT`], so a client may half-close
+/// its side once all requests are sent.
+///
+/// Nothing is read as a request before the peer presented the session token.
+/// The way it does so also picks the wire format, see [`jsonrpc`], and the
+/// codec, see [`codec`](super::codec).
+pub async fn handle_client<S>(stream: &mut S, state: ServerState) -> tokio::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mode, codec) = match authenticate(stream, &state).await? {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    let conn_id = CONNECTION_IDS.next();
+    let (reader, writer) = tokio::io::split(stream);
+    let (out_tx, out_rx) = unbounded_channel::<Outgoing>();
+    state.events.attach(conn_id, out_tx.clone(), mode, codec);
+
+    let (close_tx, close_rx) = oneshot::channel();
+    let writing = write_responses(writer, out_rx, close_rx);
+    tokio::pin!(writing);
+    let (in_flight, mut drained) = mpsc::channel(1);
+
+    let (result, reader_done) = tokio::select! {
+        r = read_requests(reader, conn_id, mode, codec, &state, out_tx, in_flight) => (r, true),
+        r = &mut writing => (r, false),
+    };
+    if reader_done {
+        // the writer keeps going while the last responses are computed
+        let writer_done = tokio::select! {
+            _ = tokio::time::timeout(DRAIN_TIMEOUT, drained.recv()) => false,
+            _ = &mut writing => true,
+        };
+        if !writer_done {
+            // queued replies, such as the protocol error that ended the
+            // connection, still go out
+            let _ = close_tx.send(());
+            let _ = tokio::time::timeout(FLUSH_TIMEOUT, writing).await;
+        }
+    }
+
+    // drop whatever this connection left behind
+    state.events.detach(conn_id);
+    if let Ok(mut map) = state.pending.lock() {
+        map.retain(|(conn, _), _| *conn != conn_id);
+    }
+    result
+}
+
+/// Reads the hello frame and answers it, see [`handshake`]. A native
+/// `{"auth": token, ..}` frame is answered with `[0, 0, "ok", info]`, a
+/// JSON-RPC `auth` call with `info` as its result; a wrong token or protocol
+/// version gets an error in the same format. The answer is always JSON.
+/// Returns the wire format and codec when the connection may continue.
+async fn authenticate<S>(
     stream: &mut S,
-    proxy: crate::utils::FrameEventLoopProxy,
-    pending: crate::utils::PendingMap,
-) -> tokio::io::Result<()>
+    state: &ServerState,
+) -> tokio::io::Result<Option<(WireMode, Codec)>>
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
+    let frame = tokio::time::timeout(AUTH_TIMEOUT, read_frame(stream, MAX_AUTH_FRAME_SIZE)).await;
+    let first: Value = match frame {
+        Ok(Ok(Some(frame))) => serde_json::from_slice(&frame).unwrap_or(Value::Null),
+        // closed before saying anything
+        Ok(Ok(None)) => return Ok(None),
+        // timed out, oversized or broken
+        _ => Value::Null,
+    };
+
+    let (mode, params) = match first.get("jsonrpc") {
+        Some(_) => (WireMode::JsonRpc, jsonrpc::auth_params(&first)),
+        None => (WireMode::Native, Some(&first)),
+    };
+    let hello = params.and_then(|params| Hello::deserialize(params).ok());
+    let verdict = match hello {
+        Some(hello) if token_matches(&hello.token, &state.config.auth_token) => hello.check(mode),
+        _ => Err(ApiError::PermissionDenied(
+            "authentication failed".to_string(),
+        )),
+    };
+
+    let info = handshake::server_info(&state.namespaces, *verdict.as_ref().unwrap_or(&Codec::Json));
+    let resp = match &verdict {
+        Ok(_) => ApiResponse(0, code::OK, "ok".to_string(), info.into()),
+        Err(err) => {
+            eprintln!("[platform] Rejected connection: {}", err);
+            let mut details = err.details();
+            // only a peer that knows the token learns what the server offers
+            if let ApiError::Protocol(_) = err {
+                details["server"] = info;
+            }
+            ApiResponse(0, err.code(), err.to_string(), details.into())
+        }
+    };
+    let sent = match mode {
+        WireMode::Native => send_now(stream, &resp).await,
+        WireMode::JsonRpc => {
+            let id = first.get("id").cloned().unwrap_or(Value::Null);
+            send_now(stream, &jsonrpc::response(id, &resp)).await
+        }
+    };
+    match verdict {
+        Ok(codec) => sent.map(|_| Some((mode, codec))),
+        // the peer may already be gone
+        Err(_) => Ok(None),
+    }
+}
+
+/// Compares in time independent of where the first difference is.
+pub(crate) fn token_matches(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn send_now<W, T>(writer: &mut W, resp: &T) -> tokio::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    write_frame(writer, &serde_json::to_vec(resp)?).await
+}
+
+async fn read_requests<R>(
+    mut reader: R,
+    conn_id: ConnectionId,
+    mode: WireMode,
+    codec: Codec,
+    state: &ServerState,
+    out_tx: UnboundedSender<Outgoing>,
+    in_flight: InFlight,
+) -> tokio::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let max_violations = state.config.max_violations;
+    let mut violations = 0;
+    // JSON-RPC ids are arbitrary values, so those requests get internal ones
+    let ids = IdCounter::new();
+    let calls = CallTable::default();
     loop {
-        // === 1. Länge lesen ===
-        let mut len_buf = [0u8; 4];
-        if let Err(_) | Ok(0) = stream.read_exact(&mut len_buf).await {
-            return Ok(()); // Verbindung beendet
-        }
-        let len = u32::from_le_bytes(len_buf) as usize;
-
-        // === 2. Nachricht lesen ===
-        let mut buf = vec![0u8; len];
-        stream.read_exact(&mut buf).await?;
-        let request_str = match String::from_utf8(buf) {
-            Ok(s) => s,
-            Err(_) => continue,
+        // === 1. Frame lesen ===
+        let buf = match read_frame(&mut reader, state.config.max_frame_size).await {
+            Ok(Some(buf)) => buf,
+            Ok(None) => return Ok(()), // Verbindung beendet
+            Err(e) => match FrameTooLarge::find(&e) {
+                // the payload is still in the stream, so there is no way
+                // to find the next frame
+                Some(too_large) => {
+                    let resp = ApiResponse::error(0, &ApiError::Protocol(too_large.to_string()));
+                    match mode {
+                        WireMode::Native => send_response(&out_tx, codec, &resp),
+                        WireMode::JsonRpc => {
+                            send_response(&out_tx, codec, &jsonrpc::response(Value::Null, &resp))
+                        }
+                    }
+                    return Ok(());
+                }
+                None => return Err(e),
+            },
         };
 
-        // === 3. JSON in ApiRequest parsen ===
-        let req: crate::api_manager::ApiRequest = match serde_json::from_str(&request_str) {
-            Ok(req) => req,
-            Err(e) => {
-                eprintln!("[platform] JSON parse error: {:?}", e);
-                continue;
+        // === 2.-5. Dekodieren, einreihen und beantworten ===
+        let valid = match mode {
+            WireMode::Native => serve_native(&buf, codec, conn_id, state, &out_tx, &in_flight),
+            WireMode::JsonRpc => {
+                serve_json_rpc(&buf, &ids, &calls, conn_id, state, &out_tx, &in_flight)
             }
         };
+        if !valid {
+            violations += 1;
+            if violations >= max_violations {
+                eprintln!(
+                    "[platform] Closing connection {} after {} malformed frames",
+                    conn_id, violations
+                );
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Serves one `[id, method, args, label?, timeout_ms?]` frame. Returns
+/// false when the frame was malformed.
+fn serve_native(
+    buf: &[u8],
+    codec: Codec,
+    conn_id: ConnectionId,
+    state: &ServerState,
+    out_tx: &UnboundedSender<Outgoing>,
+    in_flight: &InFlight,
+) -> bool {
+    let req = match parse_request(buf, codec) {
+        Ok(req) => req,
+        Err(resp) => {
+            send_response(out_tx, codec, &resp);
+            return false;
+        }
+    };
+    match dispatch(req, conn_id, state) {
+        Dispatched::Done(resp) => send_response(out_tx, codec, &resp),
+        dispatched => {
+            let out_tx = out_tx.clone();
+            let pending = state.pending.clone();
+            let in_flight = in_flight.clone();
+            tokio::spawn(async move {
+                let _in_flight = in_flight;
+                let chunks = ChunkFrame::Native(codec);
+                let resp = dispatched.wait(conn_id, &pending, &out_tx, chunks).await;
+                send_response(&out_tx, codec, &resp);
+            });
+        }
+    }
+    true
+}
 
-        // === 4. Future registrieren ===
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        {
-            let mut map = pending.lock().unwrap();
-            map.insert(req.0.clone(), tx);
+/// Serves one JSON-RPC request, notification or batch. All calls of a batch
+/// run concurrently and are answered together; notifications get no reply.
+/// Returns false when the frame or one of its entries was malformed.
+fn serve_json_rpc(
+    buf: &[u8],
+    ids: &IdCounter,
+    table: &CallTable,
+    conn_id: ConnectionId,
+    state: &ServerState,
+    out_tx: &UnboundedSender<Outgoing>,
+    in_flight: &InFlight,
+) -> bool {
+    let frame = match jsonrpc::parse(buf, ids) {
+        Ok(frame) => frame,
+        Err(reply) => {
+            send_response(out_tx, Codec::Json, &reply);
+            return false;
         }
+    };
+    let batch = frame.batch;
+    let mut valid = true;
+    let calls: Vec<_> = frame
+        .calls
+        .into_iter()
+        .map(|call| match call {
+            Call::Run { id, req } if req.1 == CANCEL_METHOD => {
+                let resp = cancel_by_json_rpc_id(conn_id, &req, table, &state.pending);
+                Ok((id, req.0, Dispatched::Done(resp)))
+            }
+            Call::Run { id, req } => {
+                if let Some(id) = &id {
+                    table.insert(id, req.0);
+                }
+                Ok((id, req.0, dispatch(req, conn_id, state)))
+            }
+            Call::Invalid(reply) => {
+                valid = false;
+                Err(reply)
+            }
+        })
+        .collect();
 
-        let _ = proxy.send_event(crate::utils::UserEvent::Request(req.clone()));
+    let out_tx = out_tx.clone();
+    let pending = state.pending.clone();
+    let table = table.clone();
+    let in_flight = in_flight.clone();
+    tokio::spawn(async move {
+        let _in_flight = in_flight;
+        // waited for side by side, so a slow call holds up only the reply
+        let waits: FuturesOrdered<_> = calls
+            .into_iter()
+            .map(|call| async {
+                match call {
+                    Ok((id, internal, dispatched)) => {
+                        let chunks = ChunkFrame::JsonRpc(id.clone());
+                        let resp = dispatched.wait(conn_id, &pending, &out_tx, chunks).await;
+                        let id = id?;
+                        table.remove(&id, internal);
+                        Some(jsonrpc::response(id, &resp))
+                    }
+                    Err(reply) => Some(reply),
+                }
+            })
+            .collect();
+        let mut replies: Vec<Value> = waits.filter_map(|reply| async { reply }).collect().await;
+        if batch {
+            // a batch of notifications is not answered at all
+            if !replies.is_empty() {
+                send_response(&out_tx, Codec::Json, &replies);
+            }
+        } else if let Some(reply) = replies.pop() {
+            send_response(&out_tx, Codec::Json, &reply);
+        }
+    });
+    valid
+}
 
-        // === 5. Antwort senden ===
-        let resp = match rx.await {
+/// A request after [`dispatch`]: either answered right away or running.
+/// A streaming handler's chunks arrive through `chunks`.
+enum Dispatched {
+    Done(ApiResponse),
+    Pending {
+        req: ApiRequest,
+        rx: oneshot::Receiver<ApiResponse>,
+        chunks: Option<mpsc::Receiver<Payload>>,
+    },
+}
+
+/// How the chunks of a streamed response are framed for the connection.
+enum ChunkFrame {
+    Native(Codec),
+    /// Chunks of a notification have nobody to go to.
+    JsonRpc(Option<Value>),
+}
+
+impl ChunkFrame {
+    fn encode(&self, id: u64, chunk: Payload) -> Option<Vec<u8>> {
+        match self {
+            ChunkFrame::Native(codec) => {
+                let chunk = ApiResponse(id, code::CHUNK, "chunk".to_string(), chunk);
+                codec
+                    .encode(&chunk)
+                    .map_err(|e| eprintln!("[platform] Failed to encode chunk: {:?}", e))
+                    .ok()
+            }
+            ChunkFrame::JsonRpc(id) => Some(jsonrpc::chunk_frame(id.as_ref()?, &chunk)),
+        }
+    }
+}
+
+/// Answers control requests and duplicates directly and hands everything
+/// else to its async handler or the event loop.
+fn dispatch(req: ApiRequest, conn_id: ConnectionId, state: &ServerState) -> Dispatched {
+    // subscriptions and cancellations never reach the event loop
+    if let Some(resp) = state
+        .events
+        .handle_control(conn_id, &req)
+        .or_else(|| handle_cancel(conn_id, &req, &state.pending))
+    {
+        return Dispatched::Done(resp);
+    }
+
+    // === 4. Future registrieren ===
+    let (tx, rx) = oneshot::channel();
+    let duplicate = {
+        let mut map = state.pending.lock().unwrap();
+        match map.entry((conn_id, req.0)) {
+            std::collections::hash_map::Entry::Occupied(_) => true,
+            std::collections::hash_map::Entry::Vacant(slot) => {
+                slot.insert(tx);
+                false
+            }
+        }
+    };
+
+    if duplicate {
+        return Dispatched::Done(req.fail(&ApiError::DuplicateRequest(req.0)));
+    }
+
+    let mut chunks = None;
+    match state.apis.get(&req.1) {
+        Some(api) => {
+            let sink = if api.stream {
+                let (sink, rx) = ChunkSink::channel();
+                chunks = Some(rx);
+                sink
+            } else {
+                ChunkSink::default()
+            };
+            let fut = (api.run)(state.handle.clone(), req.clone(), sink);
+            spawn_async_api(fut, &req, conn_id, state);
+        }
+        None => {
+            let _ = state
+                .proxy
+                .send_event(UserEvent::Request(conn_id, req.clone()));
+        }
+    }
+    Dispatched::Pending { req, rx, chunks }
+}
+
+/// What a running request produced next.
+enum Progress {
+    Chunk(Payload),
+    Done(Result<ApiResponse, oneshot::error::RecvError>),
+}
+
+async fn next_chunk(chunks: &mut Option<mpsc::Receiver<Payload>>) -> Option<Payload> {
+    match chunks {
+        Some(chunks) => chunks.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+impl Dispatched {
+    /// Waits for the response, within the request's timeout if it has one.
+    /// Chunks are queued on `out_tx` as they arrive and the timeout restarts
+    /// with each of them, so it bounds the silence, not the whole stream.
+    async fn wait(
+        self,
+        conn_id: ConnectionId,
+        pending: &PendingMap,
+        out_tx: &UnboundedSender<Outgoing>,
+        framing: ChunkFrame,
+    ) -> ApiResponse {
+        let (req, mut rx, mut chunks) = match self {
+            Dispatched::Done(resp) => return resp,
+            Dispatched::Pending { req, rx, chunks } => (req, rx, chunks),
+        };
+        let window = Arc::new(Semaphore::new(STREAM_WINDOW));
+        // === 5. Antwort senden, sobald sie vorliegt ===
+        let result = loop {
+            let next = async {
+                tokio::select! {
+                    // chunks first, they were sent before the response
+                    biased;
+                    Some(chunk) = next_chunk(&mut chunks) => Progress::Chunk(chunk),
+                    result = &mut rx => Progress::Done(result),
+                }
+            };
+            let next = match req.timeout() {
+                Some(timeout) => match tokio::time::timeout(timeout, next).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        // the event loop skips requests that are no longer
+                        // pending, a streaming handler fails its next chunk
+                        pending.lock().unwrap().remove(&(conn_id, req.0));
+                        return req.fail(&ApiError::Timeout(timeout.as_millis() as u64));
+                    }
+                },
+                None => next.await,
+            };
+            match next {
+                Progress::Chunk(chunk) => {
+                    // waits while the writer is behind on this stream
+                    let permit = window.clone().acquire_owned().await.ok();
+                    if let Some(frame) = framing.encode(req.0, chunk) {
+                        let _ = out_tx.send(Outgoing {
+                            frame,
+                            _permit: permit,
+                        });
+                    }
+                }
+                Progress::Done(result) => break result,
+            }
+        };
+        // a finished handler may have left chunks behind
+        if let Some(chunks) = chunks.as_mut() {
+            while let Ok(chunk) = chunks.try_recv() {
+                if let Some(frame) = framing.encode(req.0, chunk) {
+                    let _ = out_tx.send(frame.into());
+                }
+            }
+        }
+        match result {
             Ok(resp) => resp,
-            Err(_) => crate::api_manager::ApiResponse(
-                req.0,
-                500,
-                "Internal server error".to_string(),
-                serde_json::json!(null),
-            ),
+            Err(_) => req.fail(&ApiError::Internal(
+                "the event loop dropped the request".to_string(),
+            )),
+        }
+    }
+}
+
+/// Decodes a request frame, or the protocol error answering it. The error
+/// carries the request id whenever the frame got far enough to have one.
+fn parse_request(buf: &[u8], codec: Codec) -> Result<ApiRequest, ApiResponse> {
+    let protocol_error = |id: u64, msg: String| ApiResponse::error(id, &ApiError::Protocol(msg));
+
+    let value = codec.decode(buf).map_err(|msg| protocol_error(0, msg))?;
+    let id = value
+        .0
+        .as_array()
+        .and_then(|items| items.first())
+        .and_then(rmpv::Value::as_u64)
+        .unwrap_or(0);
+    from_payload(value).map_err(|e| {
+        protocol_error(
+            id,
+            format!("expected [id, method, args, label?, timeout_ms?]: {}", e),
+        )
+    })
+}
+
+/// Runs an async handler on the runtime and resolves its pending entry, just
+/// like the event loop does for synchronous ones.
+fn spawn_async_api<F>(fut: F, req: &ApiRequest, conn_id: ConnectionId, state: &ServerState)
+where
+    F: std::future::Future<Output = ApiResponse> + Send + 'static,
+{
+    let req = req.clone();
+    let pending = state.pending.clone();
+    tokio::spawn(async move {
+        // a panicking handler only fails its own request
+        let resp = match tokio::spawn(fut).await {
+            Ok(resp) => resp,
+            Err(_) => req.fail(&ApiError::Internal(format!(
+                "handler for {} panicked",
+                req.1
+            ))),
+        };
+        // gone when the request timed out or was cancelled meanwhile
+        if let Some(sender) = pending.lock().unwrap().remove(&(conn_id, req.0)) {
+            let _ = sender.send(resp);
+        }
+    });
+}
+
+/// Method cancelling a running request of the same connection.
+const CANCEL_METHOD: &str = "request.cancel";
+
+/// Answers `request.cancel` with `[id]` args: the pending request is answered
+/// with a cancellation error right away and skipped by the event loop.
+fn handle_cancel(
+    conn_id: ConnectionId,
+    req: &ApiRequest,
+    pending: &PendingMap,
+) -> Option<ApiResponse> {
+    if req.1 != CANCEL_METHOD {
+        return None;
+    }
+    let id = match req.args().single::<u64>() {
+        Ok(id) => id,
+        Err(e) => return Some(req.fail(&ApiError::invalid_argument(0, "id", e.to_string()))),
+    };
+    Some(req.ok(cancel(conn_id, id, pending)))
+}
+
+/// `request.cancel` of a JSON-RPC connection, whose `[id]` arg is the
+/// JSON-RPC id of the request. Unknown and finished ids cancel nothing.
+fn cancel_by_json_rpc_id(
+    conn_id: ConnectionId,
+    req: &ApiRequest,
+    table: &CallTable,
+    pending: &PendingMap,
+) -> ApiResponse {
+    let id = match req.args().arg::<Value>(0, "id") {
+        Ok(id) => id,
+        Err(e) => return req.fail(&ApiError::invalid_argument(0, "id", e.to_string())),
+    };
+    let cancelled = table
+        .get(&id)
+        .is_some_and(|internal| cancel(conn_id, internal, pending));
+    req.ok(cancelled)
+}
+
+/// Answers the pending request `id` with a cancellation error; `false` when
+/// it is not pending (anymore).
+fn cancel(conn_id: ConnectionId, id: u64, pending: &PendingMap) -> bool {
+    let sender = pending.lock().unwrap().remove(&(conn_id, id));
+    match sender {
+        Some(sender) => sender
+            .send(ApiResponse::error(id, &ApiError::Cancelled))
+            .is_ok(),
+        None => false,
+    }
+}
+
+/// Periodically drops pending senders whose waiting side is gone.
+///
+/// Timeouts and cancellations clean up after themselves; this only catches
+/// entries leaked by connections that died in between.
+pub async fn sweep_pending(pending: PendingMap, period: Duration) {
+    let mut ticker = tokio::time::interval(period);
+    loop {
+        ticker.tick().await;
+        if let Ok(mut map) = pending.lock() {
+            map.retain(|_, sender| !sender.is_closed());
+        }
+    }
+}
+
+/// Writes queued frames until the channel or the connection closes. Once
+/// `close` fires, only what is already queued is written.
+async fn write_responses<W>(
+    mut writer: W,
+    mut out_rx: UnboundedReceiver<Outgoing>,
+    mut close: oneshot::Receiver<()>,
+) -> tokio::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    loop {
+        tokio::select! {
+            out = out_rx.recv() => match out {
+                Some(out) => write_frame(&mut writer, &out.frame).await?,
+                None => return Ok(()),
+            },
+            _ = &mut close => {
+                while let Ok(out) = out_rx.try_recv() {
+                    write_frame(&mut writer, &out.frame).await?;
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn send_response<T: Serialize>(out_tx: &UnboundedSender<Outgoing>, codec: Codec, resp: &T) {
+    match codec.encode(resp) {
+        Ok(frame) => {
+            // the writer is gone only when the connection is already closing
+            let _ = out_tx.send(frame.into());
+        }
+        Err(e) => eprintln!("[platform] Failed to encode response: {:?}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary::Bytes;
+
+    #[test]
+    fn compares_tokens() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("secreT", "secret"));
+        assert!(!token_matches("secret1", "secret"));
+        assert!(!token_matches("", "secret"));
+    }
+
+    #[test]
+    fn parses_well_formed_requests() {
+        let req = parse_request(br#"[7, "window.title", [], "main", 500]"#, Codec::Json).unwrap();
+        assert_eq!(req.0, 7);
+        assert_eq!(req.1, "window.title");
+        assert_eq!(req.label(), Some("main"));
+        assert_eq!(req.timeout(), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn parses_msgpack_requests_with_binary_arguments() {
+        let image = Bytes(vec![1, 2, 3]);
+        let frame = Codec::MsgPack
+            .encode(&(9, "clipboard.set_image", (1, 1, image)))
+            .unwrap();
+        let req = parse_request(&frame, Codec::MsgPack).unwrap();
+        assert_eq!(req.0, 9);
+        let bytes: Bytes = req.args().arg(2, "b64_bytes").unwrap();
+        assert_eq!(bytes.0, vec![1, 2, 3]);
+
+        let resp = parse_request(&frame[..frame.len() - 1], Codec::MsgPack).unwrap_err();
+        assert_eq!(resp.1, code::PROTOCOL);
+    }
+
+    #[test]
+    fn reports_each_kind_of_malformed_frame() {
+        let cases: [(&[u8], u64); 5] = [
+            (b"\xff\xfe[1]", 0),
+            (b"[1, \"window.title\"", 0),
+            (b"{\"id\": 1}", 0),
+            (b"[3, 4, []]", 3),
+            (b"[5, \"window.title\", [], 6]", 5),
+        ];
+        for (frame, id) in cases {
+            let resp = parse_request(frame, Codec::Json).unwrap_err();
+            assert_eq!(resp.0, id, "{:?}", String::from_utf8_lossy(frame));
+            assert_eq!(resp.1, code::PROTOCOL);
+            assert_eq!(resp.3.to_json()["kind"], "protocol");
+        }
+    }
+
+    #[tokio::test]
+    async fn forwards_chunks_ahead_of_the_response() {
+        let req: ApiRequest =
+            serde_json::from_value(serde_json::json!([4, "resource.read_stream", []])).unwrap();
+        let (tx, rx) = oneshot::channel();
+        let (sink, chunks) = ChunkSink::channel();
+        let (out_tx, mut out_rx) = unbounded_channel::<Outgoing>();
+        // more chunks than the window, so the writer has to keep up
+        let handler = tokio::spawn(async move {
+            for i in 0..3 * STREAM_WINDOW {
+                sink.send(i).await.unwrap();
+            }
+            drop(sink);
+            let _ = tx.send(ApiResponse(
+                4,
+                code::OK,
+                "ok".to_string(),
+                serde_json::json!(24).into(),
+            ));
+        });
+        let writer = tokio::spawn(async move {
+            let mut frames = Vec::new();
+            while let Some(out) = out_rx.recv().await {
+                frames.push(serde_json::from_slice::<Value>(&out.frame).unwrap());
+            }
+            frames
+        });
+
+        let dispatched = Dispatched::Pending {
+            req,
+            rx,
+            chunks: Some(chunks),
         };
+        let pending = PendingMap::default();
+        let framing = ChunkFrame::Native(Codec::Json);
+        let resp = dispatched.wait(1, &pending, &out_tx, framing).await;
+        handler.await.unwrap();
+        drop(out_tx);
 
-        let response_json = serde_json::to_string(&resp)?;
-        let resp_bytes = response_json.as_bytes();
-        let resp_len = resp_bytes.len() as u32;
+        assert_eq!(
+            (resp.1, resp.3.to_json()),
+            (code::OK, serde_json::json!(24))
+        );
+        let frames = writer.await.unwrap();
+        assert_eq!(frames.len(), 3 * STREAM_WINDOW);
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(frame, &serde_json::json!([4, code::CHUNK, "chunk", i]));
+        }
+    }
 
-        stream.write_all(&resp_len.to_le_bytes()).await?;
-        stream.write_all(resp_bytes).await?;
-        stream.flush().await?;
+    #[tokio::test]
+    async fn stops_streams_nobody_reads() {
+        let (sink, chunks) = ChunkSink::channel();
+        drop(chunks);
+        let err = sink.send(1).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ApiError>(),
+            Some(ApiError::Cancelled)
+        ));
+
+        let err = ChunkSink::default().send(1).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ApiError>(),
+            Some(ApiError::Internal(_))
+        ));
+    }
+
+    #[test]
+    fn cancels_json_rpc_requests_by_their_own_id() {
+        let pending = PendingMap::default();
+        let (tx, mut rx) = oneshot::channel();
+        pending.lock().unwrap().insert((1, 5), tx);
+        let table = CallTable::default();
+        table.insert(&serde_json::json!("a"), 5);
+
+        let cancel = |id: Value| {
+            let req: ApiRequest =
+                serde_json::from_value(serde_json::json!([9, CANCEL_METHOD, [id]])).unwrap();
+            cancel_by_json_rpc_id(1, &req, &table, &pending).3.to_json()
+        };
+        // the internal id means nothing to a JSON-RPC client
+        assert_eq!(cancel(serde_json::json!(5)), serde_json::json!(false));
+        assert_eq!(cancel(serde_json::json!("a")), serde_json::json!(true));
+        assert_eq!(rx.try_recv().unwrap().1, code::CANCELLED);
+        assert_eq!(cancel(serde_json::json!("a")), serde_json::json!(false));
+    }
+
+    #[test]
+    fn random_frames_are_rejected_without_panicking() {
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let alphabet = b"[]{}\",:0123456789abc \\\xff";
+        for _ in 0..5000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let len = (state % 24) as usize;
+            let frame: Vec<u8> = (0..len)
+                .map(|i| alphabet[((state >> (i % 8 * 8)) as usize + i) % alphabet.len()])
+                .collect();
+            for codec in [Codec::Json, Codec::MsgPack] {
+                if let Err(resp) = parse_request(&frame, codec) {
+                    assert_eq!(resp.1, code::PROTOCOL);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,144 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Hello exchange every connection starts with.
+//!
+//! The client's first frame carries the session token and, optionally, the
+//! wire-protocol version it speaks, its library version and the codec for
+//! the rest of the connection:
+//! `{"auth": token, "protocol": 1, "version": "0.1.0", "codec": "msgpack"}`.
+//! The server answers with what it offers,
+//! `{"protocol", "version", "namespaces", "features", "codecs", "codec"}`,
+//! or with a protocol error when the versions do not match or the codec is
+//! unknown, so a mismatched build fails with a clear message instead of
+//! undecodable responses.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::codec::{Codec, CODECS};
+use super::jsonrpc::WireMode;
+use crate::error::ApiError;
+
+/// Version of the request/response format. Bumped on incompatible changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Optional parts of the protocol this server implements.
+pub const FEATURES: &[&str] = &[
+    "events",
+    "cancel",
+    "timeouts",
+    "namedArgs",
+    "describe",
+    "jsonrpc",
+    "bytes",
+    "batch",
+    "stream",
+];
+
+/// First frame of a connection. JSON-RPC clients pass the same fields as the
+/// params of their `auth` call, with `token` instead of `auth`.
+#[derive(Deserialize, Debug)]
+pub struct Hello {
+    #[serde(alias = "auth")]
+    pub token: String,
+    #[serde(default)]
+    pub protocol: Option<u32>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub codec: Option<String>,
+}
+
+impl Hello {
+    /// Returns the codec for the rest of the connection, or fails for a peer
+    /// speaking another protocol version or asking for an unknown codec.
+    /// Peers that do not state a version are accepted. JSON-RPC is JSON
+    /// only.
+    pub fn check(&self, mode: WireMode) -> Result<Codec, ApiError> {
+        if let Some(protocol) = self.protocol.filter(|p| *p != PROTOCOL_VERSION) {
+            return Err(ApiError::Protocol(format!(
+                "incompatible protocol version {} (pyorion {}), this server speaks {} (pyorion {})",
+                protocol,
+                self.version.as_deref().unwrap_or("unknown"),
+                PROTOCOL_VERSION,
+                crate::get_pyorion_version()
+            )));
+        }
+        let name = self.codec.as_deref().unwrap_or(Codec::Json.name());
+        match Codec::from_name(name) {
+            Some(Codec::Json) => Ok(Codec::Json),
+            Some(codec) if mode == WireMode::Native => Ok(codec),
+            _ => Err(ApiError::Protocol(format!(
+                "unsupported codec {:?}, expected one of {:?}",
+                name, CODECS
+            ))),
+        }
+    }
+}
+
+/// What the server answers a hello with; `codec` is the one agreed on.
+pub fn server_info(namespaces: &[String], codec: Codec) -> Value {
+    json!({
+        "protocol": PROTOCOL_VERSION,
+        "version": crate::get_pyorion_version(),
+        "namespaces": namespaces,
+        "features": FEATURES,
+        "codecs": CODECS,
+        "codec": codec.name(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hello(first: Value) -> Hello {
+        Hello::deserialize(&first).unwrap()
+    }
+
+    #[test]
+    fn accepts_the_same_or_an_unstated_protocol() {
+        assert!(hello(json!({ "auth": "t" }))
+            .check(WireMode::Native)
+            .is_ok());
+        assert!(hello(json!({ "auth": "t", "protocol": PROTOCOL_VERSION }))
+            .check(WireMode::Native)
+            .is_ok());
+        assert!(hello(json!(["t", PROTOCOL_VERSION, "0.1.0"]))
+            .check(WireMode::Native)
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_another_protocol_with_both_versions() {
+        let err =
+            hello(json!({ "token": "t", "protocol": PROTOCOL_VERSION + 1, "version": "9.9" }))
+                .check(WireMode::Native)
+                .unwrap_err();
+        assert!(matches!(err, ApiError::Protocol(_)));
+        let msg = err.to_string();
+        assert!(
+            msg.contains("9.9") && msg.contains(crate::get_pyorion_version()),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn negotiates_the_codec() {
+        let msgpack = hello(json!({ "auth": "t", "codec": "msgpack" }));
+        assert_eq!(msgpack.check(WireMode::Native).unwrap(), Codec::MsgPack);
+        assert!(msgpack.check(WireMode::JsonRpc).is_err());
+        assert_eq!(
+            hello(json!({ "auth": "t" }))
+                .check(WireMode::JsonRpc)
+                .unwrap(),
+            Codec::Json
+        );
+        assert!(hello(json!({ "auth": "t", "codec": "xml" }))
+            .check(WireMode::Native)
+            .is_err());
+    }
+}
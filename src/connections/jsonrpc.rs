@@ -0,0 +1,340 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! JSON-RPC 2.0 wire mode.
+//!
+//! A connection speaks JSON-RPC when it authenticates with a JSON-RPC call,
+//! `{"jsonrpc": "2.0", "method": "auth", "params": {"token": ..}, "id": ..}`
+//! (or `"params": [token]`), instead of the native `{"auth": token}` frame.
+//! The params may also carry the protocol version, see [`super::handshake`].
+//! Requests are turned into [`ApiRequest`]s with connection-local ids, so
+//! everything behind the handler is shared with the native format.
+//!
+//! `params` may be an array or an object. The target window and the
+//! deadline, which JSON-RPC has no place for, go into the optional `label`
+//! and `timeoutMs` members of the request object. Pushed events arrive as
+//! `event` notifications with `{event, window, payload}` params, chunks of
+//! a streamed result as `chunk` notifications with `{id, data}` params.
+//! `request.cancel` takes the JSON-RPC `id` of the request to cancel, which
+//! is looked up in the connection's [`CallTable`].
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::api_manager::{ApiRequest, ApiResponse, Code};
+use crate::binary::Payload;
+use crate::error::code;
+use crate::utils::IdCounter;
+
+pub const VERSION: &str = "2.0";
+
+/// Error codes defined by the specification. Failures of the API itself
+/// keep their native code, with the details as `data`.
+#[allow(dead_code)]
+pub mod error_code {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+}
+
+/// Wire format of a connection, fixed by its first frame.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WireMode {
+    Native,
+    JsonRpc,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCall {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+    // absent for notifications, while `"id": null` is still a request
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+}
+
+fn present<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(d).map(Some)
+}
+
+/// One entry of a frame.
+pub enum Call {
+    /// A request to run; `id` is `None` for notifications.
+    Run { id: Option<Value>, req: ApiRequest },
+    /// An entry that is not a valid request, with its error response.
+    Invalid(Value),
+}
+
+pub struct Frame {
+    pub calls: Vec<Call>,
+    /// Batches are answered with an array, even of one response.
+    pub batch: bool,
+}
+
+/// Internal ids of the running requests of one connection by JSON-RPC id.
+#[derive(Clone, Default)]
+pub struct CallTable(Arc<Mutex<HashMap<String, u64>>>);
+
+impl CallTable {
+    /// Remembers that the request `id` runs as `internal`.
+    pub fn insert(&self, id: &Value, internal: u64) {
+        self.0.lock().unwrap().insert(id.to_string(), internal);
+    }
+
+    /// Forgets `id` once its request `internal` is answered, unless the
+    /// client reused the id for a newer request meanwhile.
+    pub fn remove(&self, id: &Value, internal: u64) {
+        let mut calls = self.0.lock().unwrap();
+        if calls.get(&id.to_string()) == Some(&internal) {
+            calls.remove(&id.to_string());
+        }
+    }
+
+    /// Internal id of the running request `id`.
+    pub fn get(&self, id: &Value) -> Option<u64> {
+        self.0.lock().unwrap().get(&id.to_string()).copied()
+    }
+}
+
+/// Parses a request or a batch. `Err` is the response for a frame that is
+/// not JSON or an empty batch.
+pub fn parse(buf: &[u8], ids: &IdCounter) -> Result<Frame, Value> {
+    let value: Value = serde_json::from_slice(buf)
+        .map_err(|e| error(Value::Null, error_code::PARSE_ERROR, &e.to_string(), None))?;
+    match value {
+        Value::Array(items) if items.is_empty() => Err(error(
+            Value::Null,
+            error_code::INVALID_REQUEST,
+            "empty batch",
+            None,
+        )),
+        Value::Array(items) => Ok(Frame {
+            calls: items.into_iter().map(|item| call(item, ids)).collect(),
+            batch: true,
+        }),
+        other => Ok(Frame {
+            calls: vec![call(other, ids)],
+            batch: false,
+        }),
+    }
+}
+
+fn call(value: Value, ids: &IdCounter) -> Call {
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let invalid =
+        |msg: &str| Call::Invalid(error(id.clone(), error_code::INVALID_REQUEST, msg, None));
+
+    let raw: RawCall = match serde_json::from_value(value) {
+        Ok(raw) => raw,
+        Err(e) => return invalid(&e.to_string()),
+    };
+    if raw.jsonrpc != VERSION {
+        return invalid("jsonrpc must be \"2.0\"");
+    }
+    let params = match raw.params {
+        None => json!([]),
+        Some(params @ (Value::Array(_) | Value::Object(_))) => params,
+        Some(_) => return invalid("params must be an array or an object"),
+    };
+    let req = json!([ids.next(), raw.method, params, raw.label, raw.timeout_ms]);
+    match serde_json::from_value(req) {
+        Ok(req) => Call::Run { id: raw.id, req },
+        Err(e) => invalid(&e.to_string()),
+    }
+}
+
+/// The params of an `auth` call, if `first` is one. They hold the fields
+/// of a [`Hello`](super::handshake::Hello), by name or by position.
+pub fn auth_params(first: &Value) -> Option<&Value> {
+    if first.get("method").and_then(Value::as_str) != Some("auth") {
+        return None;
+    }
+    first.get("params")
+}
+
+/// Translates a native response for the request with JSON-RPC id `id`.
+pub fn response(id: Value, resp: &ApiResponse) -> Value {
+    if resp.1 == code::OK {
+        return json!({ "jsonrpc": VERSION, "id": id, "result": resp.3 });
+    }
+    let mut data = resp.3.to_json();
+    if let Some(data) = data.as_object_mut() {
+        data.insert("code".to_string(), json!(resp.1));
+    }
+    error(id, error_code_for(resp.1), &resp.2, Some(data))
+}
+
+fn error_code_for(code: Code) -> i64 {
+    match code {
+        code::UNKNOWN_METHOD => error_code::METHOD_NOT_FOUND,
+        code::INVALID_ARGUMENTS => error_code::INVALID_PARAMS,
+        code::PROTOCOL => error_code::INVALID_REQUEST,
+        code::INTERNAL => error_code::INTERNAL_ERROR,
+        other => other as i64,
+    }
+}
+
+pub fn error(id: Value, code: i64, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": VERSION, "id": id, "error": error })
+}
+
+/// Frame of a pushed event.
+pub fn event_frame(event: &str, window: Option<&str>, payload: &Value) -> Vec<u8> {
+    let frame = json!({
+        "jsonrpc": VERSION,
+        "method": "event",
+        "params": { "event": event, "window": window, "payload": payload },
+    });
+    // a `Value` always serializes to JSON
+    serde_json::to_vec(&frame).unwrap_or_default()
+}
+
+/// Frame of one chunk of a streamed result, sent ahead of the response to
+/// the request `id`.
+pub fn chunk_frame(id: &Value, chunk: &Payload) -> Vec<u8> {
+    let frame = json!({
+        "jsonrpc": VERSION,
+        "method": "chunk",
+        "params": { "id": id, "data": chunk },
+    });
+    serde_json::to_vec(&frame).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::handshake::Hello;
+    use super::*;
+
+    fn run(call: &Call) -> (&Option<Value>, &ApiRequest) {
+        match call {
+            Call::Run { id, req } => (id, req),
+            Call::Invalid(err) => panic!("unexpected error {}", err),
+        }
+    }
+
+    #[test]
+    fn parses_requests_and_notifications() {
+        let ids = IdCounter::new();
+        let frame = parse(
+            br#"[
+                {"jsonrpc": "2.0", "method": "window.title", "id": "a", "label": "main"},
+                {"jsonrpc": "2.0", "method": "window.set_title", "params": {"title": "x"}},
+                {"jsonrpc": "2.0", "method": "window.title", "id": null, "timeoutMs": 50}
+            ]"#,
+            &ids,
+        )
+        .unwrap();
+        assert!(frame.batch);
+
+        let (id, req) = run(&frame.calls[0]);
+        assert_eq!(id, &Some(json!("a")));
+        assert_eq!(
+            (req.0, req.1.as_str(), req.label()),
+            (1, "window.title", Some("main"))
+        );
+
+        let (id, req) = run(&frame.calls[1]);
+        assert_eq!(id, &None);
+        assert_eq!(req.args().arg::<String>(0, "title").unwrap(), "x");
+
+        let (id, req) = run(&frame.calls[2]);
+        assert_eq!(id, &Some(Value::Null));
+        assert_eq!(req.4, Some(50));
+    }
+
+    #[test]
+    fn rejects_invalid_frames_and_entries() {
+        let ids = IdCounter::new();
+        let err = parse(b"{", &ids).err().unwrap();
+        assert_eq!(err["error"]["code"], error_code::PARSE_ERROR);
+        let err = parse(b"[]", &ids).err().unwrap();
+        assert_eq!(err["error"]["code"], error_code::INVALID_REQUEST);
+
+        let frame = parse(
+            br#"[1, {"jsonrpc": "1.0", "method": "m", "id": 2}, {"jsonrpc": "2.0", "method": "m", "params": 3, "id": 4}]"#,
+            &ids,
+        )
+        .unwrap();
+        let ids: Vec<Value> = frame
+            .calls
+            .iter()
+            .map(|call| match call {
+                Call::Invalid(err) => {
+                    assert_eq!(err["error"]["code"], error_code::INVALID_REQUEST);
+                    err["id"].clone()
+                }
+                Call::Run { .. } => panic!("accepted an invalid entry"),
+            })
+            .collect();
+        assert_eq!(ids, vec![Value::Null, json!(2), json!(4)]);
+    }
+
+    #[test]
+    fn maps_native_responses() {
+        let ok = ApiResponse(1, code::OK, "ok".into(), json!(42).into());
+        assert_eq!(
+            response(json!(7), &ok),
+            json!({ "jsonrpc": "2.0", "id": 7, "result": 42 })
+        );
+
+        let err = ApiResponse::error(1, &crate::error::ApiError::UnknownMethod("x".into()));
+        let resp = response(json!(7), &err);
+        assert_eq!(resp["error"]["code"], error_code::METHOD_NOT_FOUND);
+        assert_eq!(resp["error"]["data"]["code"], code::UNKNOWN_METHOD);
+
+        let err = ApiResponse::error(1, &crate::error::ApiError::WindowNotFound("w".into()));
+        assert_eq!(
+            response(json!(7), &err)["error"]["code"],
+            code::WINDOW_NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn looks_up_running_calls_by_their_json_rpc_id() {
+        let calls = CallTable::default();
+        calls.insert(&json!("a"), 1);
+        calls.insert(&json!(1), 2);
+        assert_eq!(calls.get(&json!("a")), Some(1));
+        // `1` and `"1"` are different ids
+        assert_eq!(calls.get(&json!("1")), None);
+
+        // a reused id keeps pointing at the newer request
+        calls.insert(&json!("a"), 3);
+        calls.remove(&json!("a"), 1);
+        assert_eq!(calls.get(&json!("a")), Some(3));
+        calls.remove(&json!("a"), 3);
+        assert_eq!(calls.get(&json!("a")), None);
+    }
+
+    #[test]
+    fn reads_auth_params() {
+        let by_name =
+            json!({ "jsonrpc": "2.0", "method": "auth", "params": { "token": "t" }, "id": 0 });
+        let by_position =
+            json!({ "jsonrpc": "2.0", "method": "auth", "params": ["t", 1], "id": 0 });
+        let other = json!({ "jsonrpc": "2.0", "method": "window.title", "params": ["t"], "id": 0 });
+        for first in [by_name, by_position] {
+            let hello = Hello::deserialize(auth_params(&first).unwrap()).unwrap();
+            assert_eq!(hello.token, "t");
+        }
+        assert!(auth_params(&other).is_none());
+    }
+}
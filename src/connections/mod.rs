@@ -2,9 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+pub mod client;
+pub mod codec;
+pub mod endpoint;
+pub mod events;
+pub mod framing;
 pub mod handler;
+pub mod handshake;
+pub mod jsonrpc;
+pub mod tcp_conn;
 pub mod unix_conn;
 pub mod utils;
+pub mod websocket;
 /// Starts the platform-specific connection handler.
 ///
 /// # Purpose
@@ -16,15 +25,14 @@ pub mod utils;
 ///   - Uses a Named Pipe server with Windows security descriptors.
 /// - On **Unix-like systems** → [`unix_conn::platform_main`] is called.
 ///   - Typically uses a Unix domain socket as the IPC mechanism.
+/// - When [`ServerConfig::tcp`] is set, [`tcp_conn::platform_main`]
+///   additionally listens on TCP, optionally with TLS.
 ///
 /// This allows the rest of the application to work with a unified API,
 /// regardless of the underlying operating system.
 ///
 /// # Parameters
-/// - `proxy`: A [`FrameEventLoopProxy`] that allows communication with the
-///   main event loop for dispatching events or messages.
-/// - `pending`: A shared map (`PendingMap`) that tracks pending requests or
-///   responses awaiting processing.
+/// - `state`: The [`ServerState`] shared by all connections.
 /// - `name`: A string identifier used to construct the IPC endpoint
 ///   (e.g., pipe name on Windows or socket path on Unix).
 ///
@@ -40,97 +48,130 @@ pub mod utils;
 /// - Conditional compilation (`#[cfg(windows)]` / `#[cfg(unix)]`) ensures that
 ///   only the relevant backend is compiled for the target OS.
 ///
-/// [`FrameEventLoopProxy`]: crate::utils::FrameEventLoopProxy
 /// [`windows_conn::platform_main`]: crate::windows_conn::platform_main
 /// [`unix_conn::platform_main`]: crate::unix_conn::platform_main
 pub mod windows_conn;
 use pyo3::prelude::*;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::api_manager::{AppHandle, AsyncApis};
+use crate::utils::{FrameEventLoopProxy, PendingMap};
+
+/// Limits of the IPC server, passed as `server_cfg` to `create_webframe`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerConfig {
+    /// Largest accepted request frame in bytes; a larger one closes the
+    /// connection, since its payload is never read.
+    pub max_frame_size: usize,
+    /// Malformed frames (bad UTF-8, bad JSON, wrong shape) a connection may
+    /// send before it is closed. Each one is answered with a protocol error.
+    pub max_violations: u32,
+    /// Secret every connection has to send in its first frame,
+    /// `{"auth": token}`, see [`handshake`]. The Python side creates one per
+    /// launch; without it a random token nobody knows is used, so no client
+    /// gets in.
+    pub auth_token: String,
+    /// Process ids allowed to connect. Empty allows any process of the
+    /// same user.
+    pub allowed_pids: Vec<u32>,
+    /// Additionally listen on TCP, e.g. for automation from another host.
+    pub tcp: Option<tcp_conn::TcpConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_frame_size: framing::DEFAULT_MAX_FRAME_SIZE,
+            max_violations: 3,
+            auth_token: uuid::Uuid::new_v4().simple().to_string(),
+            allowed_pids: Vec::new(),
+            tcp: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Whether a peer with this process id may connect. `None` means the
+    /// platform could not tell.
+    pub fn pid_allowed(&self, pid: Option<u32>) -> bool {
+        self.allowed_pids.is_empty() || pid.is_some_and(|pid| self.allowed_pids.contains(&pid))
+    }
+}
+
+/// Everything a client connection needs from the app; cloned per client.
+#[derive(Clone)]
+pub struct ServerState {
+    /// Forwards requests to the main event loop.
+    pub proxy: FrameEventLoopProxy,
+    /// Responses awaited by the connections.
+    pub pending: PendingMap,
+    /// Pushes events to subscribed clients.
+    pub events: events::EventHub,
+    /// Handlers that run on the runtime instead of the event loop.
+    pub apis: AsyncApis,
+    /// Reported to clients in the hello exchange.
+    pub namespaces: Arc<Vec<String>>,
+    /// Passed to every async handler.
+    pub handle: AppHandle,
+    pub config: ServerConfig,
+}
 
 #[allow(dead_code)]
-pub async fn start_connection(
-    proxy: crate::utils::FrameEventLoopProxy,
-    pending: super::utils::PendingMap,
-    name: String,
-) -> std::io::Result<()> {
+pub async fn start_connection(state: ServerState, name: String) -> std::io::Result<()> {
+    if let Some(tcp) = state.config.tcp.clone() {
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tcp_conn::platform_main(state, &tcp).await {
+                eprintln!("[TCP] Server stopped: {:?}", e);
+            }
+        });
+    }
+
     #[cfg(windows)]
     {
-        return windows_conn::platform_main(proxy, pending, &name).await;
+        return windows_conn::platform_main(state, &name).await;
     }
 
     #[cfg(unix)]
     {
-        return unix_conn::platform_main(proxy, pending, &name).await;
+        return unix_conn::platform_main(state, &name).await;
     }
 }
 
+/// One-shot request over a fresh connection.
+///
+/// Kept for callers that do not hold an [`client::IpcClient`]; it speaks the
+/// same framing as the pooled client but opens and closes a connection per
+/// message.
 #[pyo3::pyfunction]
+#[pyo3(signature = (name, message, token = None))]
 pub fn send_event_over_platform<'py>(
     py: Python<'py>,
     name: String,
     message: String,
+    token: Option<String>,
 ) -> PyResult<Bound<'py, PyAny>> {
-    #[cfg(windows)]
     let fut = async move {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        use tokio::net::windows::named_pipe::ClientOptions;
-        use tokio::time::{sleep, Duration};
-        use windows_sys::Win32::Foundation::ERROR_PIPE_BUSY;
-
-        let pipe_full_name = format!(r"\\.\pipe\{}", name);
-
-        let mut client = loop {
-            match ClientOptions::new().open(&pipe_full_name) {
-                Ok(c) => break c,
-                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) => {}
-                Err(e) => return Err(anyhow::Error::from(e)),
-            }
-            sleep(Duration::from_millis(10)).await;
-        };
-
-        // Nachricht mit Länge schicken
-        let msg_bytes = message.as_bytes();
-        let len = msg_bytes.len() as u32;
-        client.write_all(&len.to_le_bytes()).await?;
-        client.write_all(msg_bytes).await?;
-        client.flush().await?;
-
-        // Antwort lesen
-        let mut len_buf = [0u8; 4];
-        client.read_exact(&mut len_buf).await?;
-        let resp_len = u32::from_le_bytes(len_buf) as usize;
-
-        let mut resp_buf = vec![0u8; resp_len];
-        client.read_exact(&mut resp_buf).await?;
-        let resp_str = String::from_utf8_lossy(&resp_buf).to_string();
-
-        Ok::<String, anyhow::Error>(resp_str)
-    };
-
-    #[cfg(unix)]
-    let fut = async move {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        use tokio::net::UnixStream;
-
-        let path = format!("/tmp/{}", name);
-        let mut stream = UnixStream::connect(&path).await?;
-
-        // Nachricht mit Länge schicken
-        let msg_bytes = message.as_bytes();
-        let len = msg_bytes.len() as u32;
-        stream.write_all(&len.to_le_bytes()).await?;
-        stream.write_all(msg_bytes).await?;
-        stream.flush().await?;
-
-        // Antwort lesen
-        let mut len_buf = [0u8; 4];
-        stream.read_exact(&mut len_buf).await?;
-        let resp_len = u32::from_le_bytes(len_buf) as usize;
-
-        let mut resp_buf = vec![0u8; resp_len];
-        stream.read_exact(&mut resp_buf).await?;
-        let resp_str = String::from_utf8_lossy(&resp_buf).to_string();
-
-        Ok::<String, anyhow::Error>(resp_str)
+        let (mut stream, _) = client::open_session(
+            &name,
+            token.as_deref(),
+            handshake::PROTOCOL_VERSION,
+            codec::Codec::Json,
+            framing::DEFAULT_MAX_FRAME_SIZE,
+        )
+        .await?;
+        framing::write_frame(&mut stream, message.as_bytes()).await?;
+        let resp_buf = framing::read_frame(&mut stream, framing::DEFAULT_MAX_FRAME_SIZE)
+            .await?
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "connection closed before response",
+                )
+            })?;
+        Ok::<String, anyhow::Error>(String::from_utf8_lossy(&resp_buf).to_string())
     };
 
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -0,0 +1,131 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Optional TCP listener, with TLS and client certificates when configured.
+//!
+//! Connections are served by the same [`handle_client`] as the local
+//! socket, so they authenticate with the session token like every other
+//! client. Peer credentials do not exist here; use TLS with client
+//! certificates for anything beyond loopback.
+//!
+//! [`handle_client`]: crate::connections::handler::handle_client
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use serde::Deserialize;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+use super::handler::AUTH_TIMEOUT;
+use super::ServerState;
+
+/// `tcp` section of the server config.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TcpConfig {
+    /// Address to listen on, e.g. `127.0.0.1:7878`.
+    pub address: String,
+    /// Serve TLS instead of plain TCP.
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsConfig {
+    /// PEM file with the server certificate chain.
+    pub cert_path: PathBuf,
+    /// PEM file with the server's private key.
+    pub key_path: PathBuf,
+    /// PEM file with the CAs client certificates must chain to. Clients
+    /// without a valid certificate are refused when set.
+    pub client_ca_path: Option<PathBuf>,
+}
+
+pub async fn platform_main(state: ServerState, config: &TcpConfig) -> io::Result<()> {
+    let acceptor = config.tls.as_ref().map(tls_acceptor).transpose()?;
+    let listener = TcpListener::bind(&config.address).await?;
+    let local = listener.local_addr()?;
+    if acceptor.is_none() && !local.ip().is_loopback() {
+        eprintln!(
+            "[TCP] Serving plain TCP on {}; anyone on the network can reach it",
+            local
+        );
+    }
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let _ = stream.set_nodelay(true);
+        let state = state.clone();
+        let acceptor = acceptor.clone();
+
+        tokio::spawn(async move {
+            let result = match acceptor {
+                // a peer stalling the handshake must not hold the task forever
+                Some(acceptor) => {
+                    match tokio::time::timeout(AUTH_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(mut stream)) => {
+                            crate::connections::handler::handle_client(&mut stream, state).await
+                        }
+                        Ok(Err(e)) => Err(e),
+                        Err(_) => Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "TLS handshake timed out",
+                        )),
+                    }
+                }
+                None => {
+                    let mut stream = stream;
+                    crate::connections::handler::handle_client(&mut stream, state).await
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("[TCP] Client {} error: {:?}", peer, e);
+            }
+        });
+    }
+}
+
+fn tls_acceptor(config: &TlsConfig) -> io::Result<TlsAcceptor> {
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_key(&config.key_path)?;
+
+    let builder = rustls::ServerConfig::builder();
+    let builder = match &config.client_ca_path {
+        Some(path) => {
+            let mut roots = rustls::RootCertStore::empty();
+            for cert in load_certs(path)? {
+                roots.add(cert).map_err(invalid)?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .map_err(invalid)?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let server_config = builder.with_single_cert(certs, key).map_err(invalid)?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn load_certs(path: &Path) -> io::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<io::Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(invalid(format!("no certificate in {}", path.display())));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> io::Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| invalid(format!("no private key in {}", path.display())))
+}
+
+fn invalid<E: Into<Box<dyn std::error::Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e)
+}
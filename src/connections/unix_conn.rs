@@ -4,21 +4,15 @@
 
 #[cfg(unix)]
 pub async fn platform_main(
-    proxy: crate::utils::FrameEventLoopProxy,
-    pending: crate::utils::PendingMap,
+    state: crate::connections::ServerState,
     pipe_name: &str,
 ) -> std::io::Result<()> {
     use std::fs;
-    use std::os::unix::fs::PermissionsExt;
-    use std::path::PathBuf;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
     use tokio::net::UnixListener;
 
-    // Platform Secure Temp Path
-    let mut path: PathBuf = std::env::temp_dir();
-    path.push(pipe_name);
-
-    // Remove old file if necessary
-    let _ = fs::remove_file(&path);
+    // Resolve the endpoint and make sure no live server owns it
+    let path = crate::connections::endpoint::claim(pipe_name).await?;
 
     // Bind listeners
     let listener = UnixListener::bind(&path)?;
@@ -27,18 +21,34 @@ pub async fn platform_main(
     let mut perms = fs::metadata(&path)?.permissions();
     perms.set_mode(0o600);
     fs::set_permissions(&path, perms)?;
+    // the socket file is ours, so its owner is the uid peers must run as
+    let own_uid = fs::metadata(&path)?.uid();
 
     // println!("Secure UDS Server runs on{}", path.display());
 
     loop {
         let (mut stream, _) = listener.accept().await?;
-        let proxy = proxy.clone();
-        let pending = pending.clone();
+        match stream.peer_cred() {
+            Ok(cred) => {
+                let pid = cred.pid().and_then(|pid| u32::try_from(pid).ok());
+                if cred.uid() != own_uid || !state.config.pid_allowed(pid) {
+                    eprintln!(
+                        "[UDS] Rejected peer uid {} pid {:?}",
+                        cred.uid(),
+                        cred.pid()
+                    );
+                    continue;
+                }
+            }
+            Err(e) => {
+                eprintln!("[UDS] Could not read peer credentials: {:?}", e);
+                continue;
+            }
+        }
+        let state = state.clone();
 
         tokio::spawn(async move {
-            if let Err(e) =
-                crate::connections::handler::handle_client(&mut stream, proxy, pending).await
-            {
+            if let Err(e) = crate::connections::handler::handle_client(&mut stream, state).await {
                 eprintln!("[UDS] Client error: {:?}", e);
             }
         });
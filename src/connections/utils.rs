@@ -2,7 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use pyo3::{prelude::*, types::PyList, BoundObject};
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyList},
+    BoundObject,
+};
+
+use crate::binary::Payload;
 
 pub fn json_to_py<'a>(py: Python<'a>, payload_value: &'a str) -> PyResult<Py<PyAny>> {
     let payload: serde_json::Value = serde_json::from_str(payload_value)
@@ -45,3 +51,45 @@ pub fn json_to_py<'a>(py: Python<'a>, payload_value: &'a str) -> PyResult<Py<PyA
         }
     }
 }
+
+/// Like [`json_to_py`] for a decoded frame; raw bytes become `bytes`.
+pub fn payload_to_py(py: Python<'_>, value: &Payload) -> PyResult<Py<PyAny>> {
+    raw_to_py(py, &value.0)
+}
+
+fn raw_to_py(py: Python<'_>, value: &rmpv::Value) -> PyResult<Py<PyAny>> {
+    use rmpv::Value;
+
+    match value {
+        Value::Nil => Ok(py.None()),
+        Value::Boolean(b) => Ok(b.into_pyobject(py)?.clone().into_any().unbind()),
+        Value::Integer(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(i), _) => Ok(i.into_pyobject(py)?.into_any().unbind()),
+            (_, Some(i)) => Ok(i.into_pyobject(py)?.into_any().unbind()),
+            _ => Err(pyo3::exceptions::PyValueError::new_err("Invalid number")),
+        },
+        Value::F32(f) => Ok(f.into_pyobject(py)?.into_any().unbind()),
+        Value::F64(f) => Ok(f.into_pyobject(py)?.into_any().unbind()),
+        Value::String(s) => match s.as_str() {
+            Some(s) => Ok(s.into_pyobject(py)?.into_any().unbind()),
+            None => Ok(PyBytes::new(py, s.as_bytes()).into_any().unbind()),
+        },
+        Value::Binary(bytes) | Value::Ext(_, bytes) => {
+            Ok(PyBytes::new(py, bytes).into_any().unbind())
+        }
+        Value::Array(items) => {
+            let list: Vec<Py<PyAny>> = items
+                .iter()
+                .map(|v| raw_to_py(py, v))
+                .collect::<PyResult<_>>()?;
+            Ok(PyList::new(py, list)?.into_any().into())
+        }
+        Value::Map(entries) => {
+            let dict = PyDict::new(py);
+            for (k, v) in entries {
+                dict.set_item(raw_to_py(py, k)?, raw_to_py(py, v)?)?;
+            }
+            Ok(dict.into())
+        }
+    }
+}
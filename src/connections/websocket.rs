@@ -0,0 +1,347 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Embedded WebSocket server for pages using `PyOrionConnections`.
+//!
+//! [`bind`] takes a random loopback port before the first webview is built,
+//! so the socket script already carries the real URL and no port can be
+//! taken by someone else in between. Frontend messages are
+//! `{"cmd", "result_id", "error_id", "payload", "label"?}` objects.
+//! Registered Rust APIs are answered right here; anything else is pushed to
+//! Python as a [`MESSAGE_EVENT`] event and answered through
+//! `websocket.respond`. Answers are `{"result_id", "result"}` or
+//! `{"error_id", "error"}` and only go to the connection that asked.
+//!
+//! Only the app's own pages get in. [`bind`] creates a secret per launch
+//! that the socket script sends as `token` query parameter; an upgrade
+//! request without it is refused before anything else. As a second layer,
+//! the `Origin` is checked like the sender of a webview IPC message, which
+//! alone would not do: any local process can send whatever `Origin` it
+//! likes.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    net::Ipv4Addr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+    Message,
+};
+
+use super::{events::EventHub, handler::token_matches};
+use crate::{
+    api_manager::{ApiRequest, ApiResponse, AppHandle, AsyncApis, ChunkSink},
+    assets::WebSocketConfig,
+    error::{code, ApiError},
+    window::ipc,
+};
+
+/// Topic frontend messages for Python are pushed under.
+pub const MESSAGE_EVENT: &str = "websocket.message";
+
+/// One message of a page.
+#[derive(Deserialize)]
+struct WsMessage {
+    cmd: String,
+    result_id: u64,
+    error_id: u64,
+    #[serde(default)]
+    payload: Value,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// Connected pages by id, for answers that arrive from Python.
+#[derive(Clone, Default)]
+pub struct WsClients {
+    next_id: Arc<AtomicU64>,
+    out: Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<String>>>>,
+}
+
+impl WsClients {
+    fn attach(&self) -> (u64, mpsc::UnboundedReceiver<String>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.out.lock().unwrap().insert(id, tx);
+        (id, rx)
+    }
+
+    fn detach(&self, id: u64) {
+        self.out.lock().unwrap().remove(&id);
+    }
+
+    /// Queues a text message for the page `client`; `false` once it is gone.
+    pub fn send(&self, client: u64, message: String) -> bool {
+        let out = self.out.lock().unwrap();
+        out.get(&client).is_some_and(|tx| tx.send(message).is_ok())
+    }
+}
+
+/// Everything a socket connection needs from the app; cloned per client.
+#[derive(Clone)]
+pub struct WsState {
+    pub handle: AppHandle,
+    pub apis: AsyncApis,
+    pub events: EventHub,
+    pub clients: WsClients,
+    /// Path pages have to connect to, `/` for any.
+    pub path: String,
+    /// Page of the main webview, see [`ipc::is_trusted`].
+    pub root: Option<String>,
+    /// Secret pages have to send, see [`bind`].
+    pub token: String,
+}
+
+/// Binds a random loopback port and points `cfg` at it, keeping the path of
+/// the configured URL, and hands it a fresh secret for the socket script.
+/// Returns the listener, that path and the secret.
+pub fn bind(cfg: &mut WebSocketConfig) -> std::io::Result<(std::net::TcpListener, String, String)> {
+    let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    listener.set_nonblocking(true)?;
+    let path = url::Url::parse(cfg.url())
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| "/".to_string());
+    cfg.set_url(format!("ws://{}{}", listener.local_addr()?, path));
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    cfg.set_token(token.clone());
+    Ok((listener, path, token))
+}
+
+/// Accepts pages until the runtime shuts down.
+pub async fn serve(listener: std::net::TcpListener, state: WsState) -> std::io::Result<()> {
+    let listener = TcpListener::from_std(listener)?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_client(stream, state).await {
+                eprintln!("[ws] Connection failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_client(stream: TcpStream, state: WsState) -> anyhow::Result<()> {
+    let check = |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        if state.path != "/" && req.uri().path() != state.path {
+            return Err(reject(StatusCode::NOT_FOUND, "unknown path"));
+        }
+        if !token_allowed(req.uri().query(), &state.token) {
+            return Err(reject(StatusCode::UNAUTHORIZED, "missing or wrong token"));
+        }
+        let origin = req
+            .headers()
+            .get("origin")
+            .and_then(|origin| origin.to_str().ok());
+        if !origin_allowed(origin, state.root.as_deref()) {
+            return Err(reject(StatusCode::FORBIDDEN, "origin not allowed"));
+        }
+        Ok(resp)
+    };
+    let socket = tokio_tungstenite::accept_hdr_async(stream, check).await?;
+    let (mut sink, mut source) = socket.split();
+
+    let (client, mut rx) = state.clients.attach();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            if sink.send(Message::text(message)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(message) = source.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        let message: WsMessage = match serde_json::from_str(&text) {
+            Ok(message) => message,
+            Err(e) => {
+                eprintln!("[ws] Ignoring malformed message: {}", e);
+                continue;
+            }
+        };
+        // a slow command must not hold up the ones behind it
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Some(answer) = answer(&state, client, message).await {
+                state.clients.send(client, answer.to_string());
+            }
+        });
+    }
+
+    state.clients.detach(client);
+    writer.abort();
+    Ok(())
+}
+
+/// Runs one message; `None` when Python answers it later.
+async fn answer(state: &WsState, client: u64, message: WsMessage) -> Option<Value> {
+    let request = json!([
+        message.result_id,
+        &message.cmd,
+        &message.payload,
+        &message.label
+    ]);
+    let req: ApiRequest = match serde_json::from_value(request) {
+        Ok(req) => req,
+        Err(e) => {
+            let err = ApiError::Protocol(e.to_string());
+            return Some(legacy_answer(&message, &ApiResponse::error(0, &err)));
+        }
+    };
+
+    if let Some(api) = state.apis.get(&req.1) {
+        let resp = if api.stream {
+            req.fail(&ApiError::invalid_arguments(format!(
+                "{} streams its result and is only served over the IPC server",
+                req.1
+            )))
+        } else {
+            (api.run)(state.handle.clone(), req, ChunkSink::default()).await
+        };
+        return Some(legacy_answer(&message, &resp));
+    }
+
+    let call = req.clone();
+    let resp = state
+        .handle
+        .run_on_main(move |app, target, flow| {
+            let mut manager = app.api_manager()?;
+            if !manager.has_api(&call.1) {
+                return Ok(None);
+            }
+            manager.call(call, target, flow).map(Some)
+        })
+        .await
+        .unwrap_or_else(|err| Some(req.fail(&ApiError::from_anyhow(&err))));
+    if let Some(resp) = resp {
+        return Some(legacy_answer(&message, &resp));
+    }
+
+    if !state.events.has_subscribers(MESSAGE_EVENT) {
+        let err = ApiError::UnknownMethod(message.cmd.clone());
+        return Some(legacy_answer(&message, &req.fail(&err)));
+    }
+    let payload = json!({
+        "client": client,
+        "cmd": message.cmd,
+        "result_id": message.result_id,
+        "error_id": message.error_id,
+        "payload": message.payload,
+    });
+    state
+        .events
+        .emit(MESSAGE_EVENT, message.label.as_deref(), payload);
+    None
+}
+
+/// The `{result_id, result}` / `{error_id, error}` answer `PyOrionConnections`
+/// callbacks expect.
+fn legacy_answer(message: &WsMessage, resp: &ApiResponse) -> Value {
+    if resp.1 == code::OK {
+        json!({ "result_id": message.result_id, "result": resp.3 })
+    } else {
+        json!({ "error_id": message.error_id, "error": resp.2 })
+    }
+}
+
+/// Whether the `Origin` of an upgrade request is one of the app's pages.
+/// Browsers send `null` for inline HTML; other clients send none at all.
+fn origin_allowed(origin: Option<&str>, root: Option<&str>) -> bool {
+    match origin {
+        Some("null") => ipc::is_inline(root),
+        Some(origin) => origin
+            .parse()
+            .is_ok_and(|origin| ipc::is_trusted(&origin, root)),
+        None => false,
+    }
+}
+
+/// Whether the query of an upgrade request carries the launch secret.
+fn token_allowed(query: Option<&str>, token: &str) -> bool {
+    query.is_some_and(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .any(|(name, value)| name == "token" && token_matches(&value, token))
+    })
+}
+
+fn reject(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut resp = ErrorResponse::new(Some(reason.to_string()));
+    *resp.status_mut() = status;
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary::Payload;
+
+    fn message() -> WsMessage {
+        serde_json::from_value(json!({
+            "cmd": "window.title",
+            "result_id": 7,
+            "error_id": 8,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn answers_in_the_shape_of_the_callbacks() {
+        let ok = ApiResponse(
+            7,
+            code::OK,
+            "ok".into(),
+            Payload(rmpv::Value::Binary(vec![1, 2])),
+        );
+        assert_eq!(
+            legacy_answer(&message(), &ok),
+            json!({ "result_id": 7, "result": "AQI=" })
+        );
+
+        let err = ApiResponse::error(7, &ApiError::UnknownMethod("nope".into()));
+        let answer = legacy_answer(&message(), &err);
+        assert_eq!(answer["error_id"], 8);
+        assert!(answer.get("result_id").is_none());
+    }
+
+    #[test]
+    fn only_the_apps_pages_may_connect() {
+        assert!(origin_allowed(Some("wry://localhost"), None));
+        assert!(origin_allowed(Some("http://wry.localhost"), None));
+        assert!(!origin_allowed(Some("https://evil.example"), None));
+        assert!(!origin_allowed(None, None));
+
+        let dev = Some("http://localhost:5173/");
+        assert!(origin_allowed(Some("http://localhost:5173"), dev));
+        assert!(!origin_allowed(Some("http://localhost:5174"), dev));
+
+        assert!(origin_allowed(Some("null"), Some("<html><p>hi</p></html>")));
+        assert!(!origin_allowed(Some("null"), dev));
+    }
+
+    #[test]
+    fn pages_have_to_send_the_launch_token() {
+        assert!(token_allowed(Some("token=s3cret"), "s3cret"));
+        assert!(token_allowed(Some("v=1&token=s3cret"), "s3cret"));
+        assert!(!token_allowed(Some("token=guess"), "s3cret"));
+        assert!(!token_allowed(Some("token="), "s3cret"));
+        assert!(!token_allowed(Some("v=1"), "s3cret"));
+        assert!(!token_allowed(None, "s3cret"));
+    }
+}
@@ -4,63 +4,146 @@
 
 #[cfg(windows)]
 pub async fn platform_main(
-    proxy: crate::utils::FrameEventLoopProxy,
-    pending: crate::utils::PendingMap,
+    state: crate::connections::ServerState,
     pipe_name: &str,
 ) -> std::io::Result<()> {
-    use tokio::net::windows::named_pipe::ServerOptions;
-    use windows_sys::Win32::Security::{
-        InitializeSecurityDescriptor, SetSecurityDescriptorDacl, SECURITY_ATTRIBUTES,
-        SECURITY_DESCRIPTOR,
-    };
-
-    const SECURITY_DESCRIPTOR_REVISION: u32 = 1;
-    let pipe_full_name = format!(r"\\.\pipe\{}", pipe_name);
-
-    // 👉 The following applies here: Use descriptors and attributes only temporarily
-    let mut server = {
-        let mut sd: SECURITY_DESCRIPTOR = unsafe { std::mem::zeroed() };
-
-        let ok = unsafe {
-            InitializeSecurityDescriptor(&mut sd as *mut _ as *mut _, SECURITY_DESCRIPTOR_REVISION)
-        };
-        assert!(ok != 0, "InitSecurityDescriptor failed");
+    let pipe_full_name = crate::connections::endpoint::pipe_name(pipe_name)?;
 
-        let ok = unsafe {
-            SetSecurityDescriptorDacl(&mut sd as *mut _ as *mut _, 1, std::ptr::null_mut(), 0)
-        };
-        assert!(ok != 0, "SetSecurityDescriptorDacl failed");
-
-        let mut sa = SECURITY_ATTRIBUTES {
-            nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
-            lpSecurityDescriptor: &mut sd as *mut _ as *mut _,
-            bInheritHandle: 0,
-        };
-
-        unsafe {
-            ServerOptions::new()
-                .first_pipe_instance(true)
-                .create_with_security_attributes_raw(&pipe_full_name, &mut sa as *mut _ as _)
-                .unwrap()
-        }
-    }; //sd + sa are released again here → Future remains Send
+    // fails while another server owns the name
+    let mut server = create_pipe(&pipe_full_name, true)?;
 
     // println!("Secure Named Pipe Server runs on{}", pipe_full_name);
 
     loop {
         server.connect().await?;
         let mut inner = server;
-        server = ServerOptions::new().create(&pipe_full_name)?;
+        server = create_pipe(&pipe_full_name, false)?;
 
-        let proxy = proxy.clone();
-        let pending = pending.clone();
+        let pid = client_pid(&inner);
+        if !state.config.pid_allowed(pid) {
+            eprintln!("[Pipe] Rejected client pid {:?}", pid);
+            continue;
+        }
+
+        let state = state.clone();
 
         tokio::spawn(async move {
-            if let Err(e) =
-                crate::connections::handler::handle_client(&mut inner, proxy, pending).await
-            {
+            if let Err(e) = crate::connections::handler::handle_client(&mut inner, state).await {
                 eprintln!("[Pipe] Client error: {:?}", e);
             }
         });
     }
 }
+
+/// Creates an instance of the pipe that only the current user can open.
+///
+/// The descriptor only lives for this call, so no raw pointer is held
+/// across an `.await` and the server future stays `Send`.
+#[cfg(windows)]
+fn create_pipe(
+    pipe_full_name: &str,
+    first: bool,
+) -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeServer> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+    use windows_sys::Win32::Security::SECURITY_ATTRIBUTES;
+
+    let descriptor = OwnerOnly::new()?;
+    let mut sa = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor.0,
+        bInheritHandle: 0,
+    };
+    unsafe {
+        ServerOptions::new()
+            .first_pipe_instance(first)
+            .create_with_security_attributes_raw(pipe_full_name, &mut sa as *mut _ as _)
+    }
+}
+
+/// Security descriptor whose protected DACL grants access to the current
+/// user only, so other accounts on the machine cannot even open the pipe.
+#[cfg(windows)]
+struct OwnerOnly(windows_sys::Win32::Security::PSECURITY_DESCRIPTOR);
+
+#[cfg(windows)]
+impl OwnerOnly {
+    fn new() -> std::io::Result<Self> {
+        use windows_sys::Win32::Security::Authorization::{
+            ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+        };
+
+        // P: no inherited entries, A;;GA: generic all for the user only
+        let sddl: Vec<u16> = format!("D:P(A;;GA;;;{})", current_user_sid()?)
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let mut descriptor = std::ptr::null_mut();
+        let ok = unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                sddl.as_ptr(),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self(descriptor))
+    }
+}
+
+#[cfg(windows)]
+impl Drop for OwnerOnly {
+    fn drop(&mut self) {
+        unsafe { windows_sys::Win32::Foundation::LocalFree(self.0) };
+    }
+}
+
+/// SID of the user running this process, as `S-1-5-..` string.
+#[cfg(windows)]
+fn current_user_sid() -> std::io::Result<String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, LocalFree};
+    use windows_sys::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let mut token = std::ptr::null_mut();
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let mut len = 0u32;
+    // asks for the size only, which "fails" with ERROR_INSUFFICIENT_BUFFER
+    unsafe { GetTokenInformation(token, TokenUser, std::ptr::null_mut(), 0, &mut len) };
+    // u64 keeps the buffer aligned for TOKEN_USER
+    let mut buf = vec![0u64; (len as usize).div_ceil(8)];
+    let ok = unsafe { GetTokenInformation(token, TokenUser, buf.as_mut_ptr() as _, len, &mut len) };
+    let err = std::io::Error::last_os_error();
+    unsafe { CloseHandle(token) };
+    if ok == 0 {
+        return Err(err);
+    }
+
+    let user = unsafe { &*(buf.as_ptr() as *const TOKEN_USER) };
+    let mut string_sid = std::ptr::null_mut();
+    if unsafe { ConvertSidToStringSidW(user.User.Sid, &mut string_sid) } == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let sid = unsafe {
+        let len = (0..).take_while(|&i| *string_sid.add(i) != 0).count();
+        String::from_utf16_lossy(std::slice::from_raw_parts(string_sid, len))
+    };
+    unsafe { LocalFree(string_sid as _) };
+    Ok(sid)
+}
+
+/// Process id of the client connected to `pipe`.
+#[cfg(windows)]
+fn client_pid(pipe: &tokio::net::windows::named_pipe::NamedPipeServer) -> Option<u32> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::Pipes::GetNamedPipeClientProcessId;
+
+    let mut pid = 0u32;
+    let ok = unsafe { GetNamedPipeClientProcessId(pipe.as_raw_handle() as _, &mut pid) };
+    (ok != 0).then_some(pid)
+}
@@ -2,19 +2,32 @@ use anyhow::{anyhow, Result};
 use pyo3::{Py, PyAny};
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, RwLock},
 };
 use tao::{
     event_loop::ControlFlow,
     window::{Window, WindowId},
 };
 
-use crate::utils::{arc_mut, ArcMut};
+use crate::{
+    error::ApiError,
+    utils::{arc_mut, ArcMut},
+};
+
+/// Label used for the first window when no `webview.label` is configured.
+pub const DEFAULT_LABEL: &str = "root_webview";
+
+/// Labels by window id, readable without locking the context.
+pub type WindowLabels = Arc<RwLock<HashMap<WindowId, String>>>;
 
 #[derive(Clone)]
 pub struct AppContext {
     first_id: Option<WindowId>,
     pub window: Arc<Mutex<HashMap<WindowId, (Arc<Window>, Arc<wry::WebView>)>>>,
+    // label -> window id, kept in creation order
+    labels: Vec<(String, WindowId)>,
+    // the same the other way round, for window events
+    by_id: WindowLabels,
 }
 
 impl AppContext {
@@ -22,89 +35,149 @@ impl AppContext {
         Ok(arc_mut(Self {
             first_id: None,
             window: Arc::new(Mutex::new(HashMap::new())),
+            labels: Vec::new(),
+            by_id: WindowLabels::default(),
         }))
     }
 
-    pub fn _window_id(&self) -> Result<WindowId> {
-        let id = self.first_id.clone().ok_or(anyhow!("No window ID set"))?;
-        Ok(id)
+    // Shares the labels with code that must not wait for the context
+    pub fn window_labels(&self) -> WindowLabels {
+        self.by_id.clone()
+    }
+
+    // Resolves a window label to its id; `None` addresses the main window
+    pub fn window_id(&self, label: Option<&str>) -> Result<WindowId> {
+        match label {
+            Some(label) => self
+                .labels
+                .iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, id)| *id)
+                .ok_or_else(|| ApiError::WindowNotFound(label.to_string()).into()),
+            None => self
+                .first_id
+                .ok_or_else(|| ApiError::WindowNotFound(DEFAULT_LABEL.to_string()).into()),
+        }
+    }
+
+    // Returns all window labels in creation order
+    pub fn labels(&self) -> Vec<String> {
+        self.labels.iter().map(|(l, _)| l.clone()).collect()
+    }
+
+    // Picks the label for a new window, rejecting duplicates
+    pub fn unique_label(&self, requested: Option<String>) -> Result<String> {
+        match requested {
+            Some(label) if self.labels.iter().any(|(l, _)| *l == label) => Err(
+                ApiError::invalid_arguments(format!("window label {:?} already exists", label))
+                    .into(),
+            ),
+            Some(label) => Ok(label),
+            None if self.labels.is_empty() => Ok(DEFAULT_LABEL.to_string()),
+            None => Ok(format!("window-{}", uuid::Uuid::new_v4().simple())),
+        }
     }
 
     pub fn close_window(
         &mut self,
+        id: WindowId,
         mp_event: Py<PyAny>,
         flow: &mut tao::event_loop::ControlFlow,
     ) -> Result<()> {
-        if let Some(id) = self.first_id.take() {
-            let mut guard = self
-                .window
-                .lock()
-                .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
-
-            if let Some((_window, _webview)) = guard.remove(&id) {
-                if guard.is_empty() {
-                    // last window -> trigger Python event and end loop
-                    pyo3::Python::with_gil(|py| {
-                        if let Err(e) = mp_event.call_method0(py, "set") {
-                            e.print(py);
-                        }
-                        py.check_signals().unwrap();
-                    });
-                    *flow = ControlFlow::Exit;
-                }
-                Ok(())
-            } else {
-                Err(anyhow!("Window with id {:?} not found", id))
+        let mut guard = self
+            .window
+            .lock()
+            .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
+
+        if let Some((window, webview)) = guard.remove(&id) {
+            // the webview has to go before its parent window
+            drop(webview);
+            drop(window);
+
+            self.labels.retain(|(_, i)| *i != id);
+            self.by_id.write().unwrap().remove(&id);
+            if self.first_id == Some(id) {
+                self.first_id = self.labels.first().map(|(_, i)| *i);
+            }
+
+            if guard.is_empty() {
+                // last window -> trigger Python event and end loop
+                pyo3::Python::with_gil(|py| {
+                    if let Err(e) = mp_event.call_method0(py, "set") {
+                        e.print(py);
+                    }
+                    py.check_signals().unwrap();
+                });
+                *flow = ControlFlow::Exit;
             }
+            Ok(())
         } else {
-            Err(anyhow!("No window ID set"))
+            Err(anyhow!("Window with id {:?} not found", id))
         }
     }
 
+    // Closes every window, which ends the loop once the last one is gone
+    pub fn close_all(
+        &mut self,
+        mp_event: Py<PyAny>,
+        flow: &mut tao::event_loop::ControlFlow,
+    ) -> Result<()> {
+        let ids: Vec<WindowId> = self.labels.iter().map(|(_, id)| *id).collect();
+        for id in ids {
+            let mp_event = pyo3::Python::with_gil(|py| mp_event.clone_ref(py));
+            self.close_window(id, mp_event, flow)?;
+        }
+        Ok(())
+    }
+
     // Method for adding a window and WebViews
-    pub fn add_window(&mut self, id: WindowId, window: Arc<Window>, webview: Arc<wry::WebView>) {
+    pub fn add_window(
+        &mut self,
+        id: WindowId,
+        label: String,
+        window: Arc<Window>,
+        webview: Arc<wry::WebView>,
+    ) -> Result<()> {
+        if self.labels.iter().any(|(l, _)| *l == label) {
+            return Err(anyhow!("Window with label {:?} already exists", label));
+        }
         let mut guard = self
             .window
             .lock()
-            .map_err(|e| anyhow!("Mutex poison error: {}", e))
-            .unwrap();
+            .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
         guard.insert(id, (window, webview));
+        self.by_id.write().unwrap().insert(id, label.clone());
+        self.labels.push((label, id));
         if self.first_id.is_none() {
             self.first_id = Some(id);
         }
+        Ok(())
     }
 
-    // Returns the first window
-    pub fn get_window(&self) -> Result<Arc<Window>> {
-        if let Some(id) = self.first_id {
-            let guard = self
-                .window
-                .lock()
-                .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
-            guard
-                .get(&id)
-                .map(|(window, _)| Arc::clone(window))
-                .ok_or_else(|| anyhow!("Window with id {:?} not found", id))
-        } else {
-            Err(anyhow!("No window ID set"))
-        }
+    // Returns the window addressed by `label`, or the main window
+    pub fn get_window(&self, label: Option<&str>) -> Result<Arc<Window>> {
+        let id = self.window_id(label)?;
+        let guard = self
+            .window
+            .lock()
+            .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
+        guard
+            .get(&id)
+            .map(|(window, _)| Arc::clone(window))
+            .ok_or_else(|| anyhow!("Window with id {:?} not found", id))
     }
 
-    // Returns the WebView for the first window
-    #[allow(dead_code)]
-    pub fn get_webview(&self) -> Result<Arc<wry::WebView>> {
-        if let Some(id) = self.first_id {
-            let guard = self
-                .window
-                .lock()
-                .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
-            guard
-                .get(&id)
-                .map(|(_, webview)| Arc::clone(webview))
-                .ok_or_else(|| anyhow!("WebView with id {:?} not found", id))
-        } else {
-            Err(anyhow!("No WebView ID set"))
-        }
+    // Returns the WebView addressed by `label`, or the one of the main window
+    pub fn get_webview(&self, label: Option<&str>) -> Result<Arc<wry::WebView>> {
+        let id = self.window_id(label)?;
+        let guard = self
+            .window
+            .lock()
+            .map_err(|e| anyhow!("Mutex poison error: {}", e))?;
+        guard
+            .get(&id)
+            .map(|(_, webview)| Arc::clone(webview))
+            .ok_or_else(|| anyhow!("WebView with id {:?} not found", id))
     }
 }
 
@@ -118,6 +191,7 @@ impl std::fmt::Debug for AppContext {
                 .field("first_id", &self.first_id)
                 .field("window_count", &guard.len())
                 .field("window_ids", &guard.keys().collect::<Vec<_>>())
+                .field("labels", &self.labels())
                 .finish(),
             Err(_) => f
                 .debug_struct("AppContext")
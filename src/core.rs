@@ -3,15 +3,22 @@
 // SPDX-License-Identifier: MIT
 
 use anyhow::Result;
-use pyo3::Python;
+use pyo3::{Py, PyAny, Python};
 use pyorion_options::window::WindowOptions;
+use serde_json::{json, Value};
 use std::sync::Arc;
+use tao::{
+    event::{ElementState, WindowEvent},
+    event_loop::ControlFlow,
+    window::WindowId,
+};
 
 use crate::{
-    api_manager::{ApiManager, ApiResponse},
-    context::AppContext,
+    api_manager::{ApiManager, ApiResponse, AppHandle, AsyncApi, AsyncApis},
+    connections::{events::EventHub, websocket::WsClients},
+    context::{AppContext, WindowLabels},
     lock,
-    utils::{ArcMut, FrameEventLoop, FrameEventLoopProxy, PendingMap, UserEvent},
+    utils::{ArcMut, FrameEventLoop, FrameEventLoopProxy, PendingMap, RequestKey, UserEvent},
 };
 
 #[allow(dead_code)]
@@ -21,7 +28,13 @@ pub struct App {
     pub runtime_handel: std::sync::Arc<tokio::runtime::Handle>,
     pub proxy: FrameEventLoopProxy,
     response_map: PendingMap,
+    events: EventHub,
+    apis: AsyncApis,
+    ws_clients: WsClients,
     pub ctx: ArcMut<AppContext>,
+    window_labels: WindowLabels,
+    sock_cfg: Option<crate::assets::WebSocketConfig>,
+    close_event: Py<PyAny>,
 }
 
 impl App {
@@ -30,6 +43,8 @@ impl App {
         sock_cfg: Option<crate::assets::WebSocketConfig>,
         options: &WindowOptions,
         uds_name: String,
+        close_event: Py<PyAny>,
+        server_cfg: crate::connections::ServerConfig,
     ) -> Result<std::sync::Arc<App>> {
         let proxy = event_loop.create_proxy();
 
@@ -39,14 +54,27 @@ impl App {
                 .build()?,
         );
 
-        let (window_id, window, webview) =
-            crate::window::create_frame(&event_loop, options, sock_cfg)?;
-
         let ctx = AppContext::new()?;
 
+        // the port has to be known before the socket script is injected
+        let mut sock_cfg = sock_cfg;
+        let ws_listener = match sock_cfg.as_mut() {
+            Some(cfg) if cfg.embedded() => Some(crate::connections::websocket::bind(cfg)?),
+            _ => None,
+        };
+
         {
             let mut ctx_lock = lock!(ctx)?;
-            ctx_lock.add_window(window_id, Arc::new(window), Arc::new(webview));
+            let label = ctx_lock.unique_label(options.webview.label.clone())?;
+            let (window_id, window, webview) = crate::window::create_frame(
+                &event_loop,
+                options,
+                &label,
+                sock_cfg.clone(),
+                proxy.clone(),
+                rt.handle().clone(),
+            )?;
+            ctx_lock.add_window(window_id, label, Arc::new(window), Arc::new(webview))?;
         }
 
         let handle = rt.handle().clone();
@@ -58,6 +86,12 @@ impl App {
             let mut api_manager = lock!(api_manager)?;
             crate::api::register_api_instances(&mut api_manager);
         }
+        let (apis, namespaces) = {
+            let mut m = lock!(api_manager)?;
+            // `async_apis` registers `system.describe`, so it goes first
+            let apis = m.async_apis();
+            (apis, m.namespaces())
+        };
 
         let app = Arc::new(Self {
             api_manager: api_manager.clone(),
@@ -65,7 +99,13 @@ impl App {
             runtime_handel: std::sync::Arc::new(handle),
             proxy,
             response_map: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            events: EventHub::new(),
+            apis: apis.clone(),
+            ws_clients: WsClients::default(),
             ctx: ctx.clone(),
+            window_labels: lock!(ctx)?.window_labels(),
+            sock_cfg,
+            close_event,
         });
 
         {
@@ -74,11 +114,40 @@ impl App {
         }
         let map = app.clone().response_map.clone();
 
-        rt.spawn(crate::connections::start_connection(
-            cloned_proxy.clone(),
-            map,
-            uds_name.to_string(),
+        rt.spawn(crate::connections::handler::sweep_pending(
+            map.clone(),
+            std::time::Duration::from_secs(30),
         ));
+        if let Some((listener, path, token)) = ws_listener {
+            let state = crate::connections::websocket::WsState {
+                handle: AppHandle::new(cloned_proxy.clone()),
+                apis: apis.clone(),
+                events: app.events.clone(),
+                clients: app.ws_clients.clone(),
+                path,
+                root: options.webview.render_protocol.clone(),
+                token,
+            };
+            rt.spawn(async move {
+                if let Err(e) = crate::connections::websocket::serve(listener, state).await {
+                    eprintln!("[ws] WebSocket server stopped: {:?}", e);
+                }
+            });
+        }
+        let state = crate::connections::ServerState {
+            proxy: cloned_proxy.clone(),
+            pending: map,
+            events: app.events.clone(),
+            apis,
+            namespaces: Arc::new(namespaces),
+            handle: AppHandle::new(cloned_proxy),
+            config: server_cfg,
+        };
+        rt.spawn(async move {
+            if let Err(e) = crate::connections::start_connection(state, uds_name).await {
+                eprintln!("[platform] IPC server stopped: {:?}", e);
+            }
+        });
         Ok(app)
     }
 
@@ -90,20 +159,51 @@ impl App {
     pub fn app_context(&self) -> Result<std::sync::MutexGuard<'_, AppContext>> {
         lock!(self.ctx)
     }
+    pub fn sock_cfg(&self) -> Option<crate::assets::WebSocketConfig> {
+        self.sock_cfg.clone()
+    }
+    /// The async API registered as `name`, if any.
+    pub fn async_api(&self, name: &str) -> Option<&AsyncApi> {
+        self.apis.get(name)
+    }
+    pub fn events(&self) -> &EventHub {
+        &self.events
+    }
+    /// Pages connected to the embedded WebSocket server.
+    pub fn ws_clients(&self) -> &WsClients {
+        &self.ws_clients
+    }
+
+    /// Closes a single window. Python's close event is only set once the
+    /// last window is gone.
+    pub fn close_window(&self, id: WindowId, flow: &mut ControlFlow) -> Result<()> {
+        let mp_event = Python::with_gil(|py| self.close_event.clone_ref(py));
+        lock!(self.ctx)?.close_window(id, mp_event, flow)
+    }
+
+    /// Pushes an event for `window_id` to every subscribed client.
+    pub fn emit(&self, topic: &str, window_id: WindowId, payload: Value) {
+        // events can fire while an API call holds the context (e.g. during
+        // window creation), so the labels are kept outside of it
+        let label = self.window_labels.read().unwrap().get(&window_id).cloned();
+        self.events.emit(topic, label.as_deref(), payload);
+    }
+
+    /// Whether a request is still awaited; timed out and cancelled ones are not.
+    pub fn is_pending(&self, key: &RequestKey) -> bool {
+        self.response_map.lock().unwrap().contains_key(key)
+    }
+
     #[allow(dead_code)]
-    pub fn respond(&self, key: u8, response: ApiResponse) {
+    pub fn respond(&self, key: RequestKey, response: ApiResponse) {
         if let Some(sender) = self.response_map.lock().unwrap().remove(&key) {
             let _ = sender.send(response);
         } else {
-            eprintln!("No transmitter found for key {}", key);
+            eprintln!("No transmitter found for key {:?}", key);
         }
     }
 
-    pub fn run(
-        self: Arc<Self>,
-        event_loop: FrameEventLoop,
-        _mp_event: pyo3::Py<pyo3::PyAny>,
-    ) -> Result<()> {
+    pub fn run(self: Arc<Self>, event_loop: FrameEventLoop) -> Result<()> {
         let api_manager = self.api_manager.clone();
         let ctx = self.ctx.clone();
         let this = self.clone();
@@ -112,29 +212,75 @@ impl App {
             *control_flow = tao::event_loop::ControlFlow::Wait;
 
             match event {
-                tao::event::Event::WindowEvent { event, .. } => match event {
-                    tao::event::WindowEvent::CloseRequested => {
-                        let mp_event = Python::with_gil(|py| _mp_event.clone_ref(py));
-                        let _ = ctx.lock().unwrap().close_window(mp_event, control_flow);
+                tao::event::Event::WindowEvent {
+                    window_id, event, ..
+                } => match event {
+                    WindowEvent::CloseRequested => {
+                        this.emit("window.closeRequested", window_id, json!(null));
+                        let _ = this.close_window(window_id, control_flow);
+                    }
+                    WindowEvent::Resized(size) => this.emit(
+                        "window.resized",
+                        window_id,
+                        json!({ "width": size.width, "height": size.height }),
+                    ),
+                    WindowEvent::Moved(pos) => {
+                        this.emit("window.moved", window_id, json!({ "x": pos.x, "y": pos.y }))
                     }
+                    WindowEvent::Focused(focused) => {
+                        this.emit("window.focused", window_id, json!({ "focused": focused }))
+                    }
+                    WindowEvent::ThemeChanged(theme) => this.emit(
+                        "window.themeChanged",
+                        window_id,
+                        json!({ "theme": format!("{:?}", theme).to_lowercase() }),
+                    ),
+                    WindowEvent::DroppedFile(path) => this.emit(
+                        "window.fileDropped",
+                        window_id,
+                        json!({ "path": path.to_string_lossy() }),
+                    ),
+                    WindowEvent::KeyboardInput { event, .. } => this.emit(
+                        "window.keyboardInput",
+                        window_id,
+                        json!({
+                            "key": format!("{:?}", event.logical_key),
+                            "code": format!("{:?}", event.physical_key),
+                            "text": event.text,
+                            "pressed": event.state == ElementState::Pressed,
+                            "repeat": event.repeat,
+                        }),
+                    ),
                     _ => {}
                 },
                 tao::event::Event::UserEvent(event) => match event {
-                    UserEvent::Request(req) => {
+                    UserEvent::Request(conn_id, req) => {
+                        let key = (conn_id, req.0);
+                        if !this.is_pending(&key) {
+                            // nobody waits for it any longer
+                            return;
+                        }
+                        let fallback = req.clone();
                         let mut manager = api_manager.lock().unwrap();
                         match manager.call(req, target, control_flow) {
-                            Ok(res) => this.respond(res.0, res),
+                            Ok(res) => this.respond(key, res),
                             Err(err) => {
                                 eprintln!("API call failed: {:?}", err);
-                                // evtl. ein ApiResponse mit Fehler zurückschicken
+                                let err = crate::error::ApiError::from_anyhow(&err);
+                                this.respond(key, fallback.fail(&err));
                             }
                         };
                     }
+                    UserEvent::WebviewIpc(label, body) => {
+                        crate::window::ipc::serve(&this, label, &body, target, control_flow)
+                    }
+                    UserEvent::Dispatch(task) => task(&this, target, control_flow),
                     UserEvent::Shutdown => {
-                        let mp_event = Python::with_gil(|py| _mp_event.clone_ref(py));
-                        let _ = ctx.lock().unwrap().close_window(mp_event, control_flow);
+                        let mp_event = Python::with_gil(|py| this.close_event.clone_ref(py));
+                        let _ = ctx.lock().unwrap().close_all(mp_event, control_flow);
                     }
                 },
+                tao::event::Event::LoopDestroyed => crate::connections::endpoint::release_all(),
                 _ => {}
             }
         });
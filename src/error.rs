@@ -0,0 +1,191 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Error taxonomy of the IPC API.
+//!
+//! Every failure is answered as `[id, code, msg, details]`: `code` is one of
+//! the stable numbers below, `msg` is human readable and `details` is an
+//! object with at least a `kind` field, so clients never have to parse `msg`.
+
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::api_manager::Code;
+
+/// Stable numeric error codes. `0` means success.
+#[allow(dead_code)]
+pub mod code {
+    use super::Code;
+
+    pub const OK: Code = 0;
+    /// Not an error: one chunk of a streamed result, more frames follow.
+    pub const CHUNK: Code = 100;
+    pub const INTERNAL: Code = 1000;
+    pub const UNKNOWN_METHOD: Code = 1001;
+    pub const INVALID_ARGUMENTS: Code = 1002;
+    pub const WINDOW_NOT_FOUND: Code = 1003;
+    pub const PERMISSION_DENIED: Code = 1004;
+    pub const IO: Code = 1005;
+    pub const PLATFORM_UNSUPPORTED: Code = 1006;
+    pub const TIMEOUT: Code = 1007;
+    pub const CANCELLED: Code = 1008;
+    pub const DUPLICATE_REQUEST: Code = 1009;
+    pub const PROTOCOL: Code = 1010;
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    UnknownMethod(String),
+    /// `argument` is the failing parameter name, `index` its position.
+    InvalidArguments {
+        message: String,
+        argument: Option<String>,
+        index: Option<usize>,
+    },
+    WindowNotFound(String),
+    PermissionDenied(String),
+    Io(String),
+    PlatformUnsupported(String),
+    Timeout(u64),
+    Cancelled,
+    DuplicateRequest(u64),
+    /// The frame itself was unusable: too large, not UTF-8, not JSON or not
+    /// shaped like a request.
+    Protocol(String),
+    Internal(String),
+}
+
+impl ApiError {
+    #[allow(dead_code)]
+    pub fn invalid_arguments<S: Into<String>>(message: S) -> Self {
+        ApiError::InvalidArguments {
+            message: message.into(),
+            argument: None,
+            index: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn invalid_argument<S: Into<String>>(index: usize, name: &str, message: S) -> Self {
+        ApiError::InvalidArguments {
+            message: message.into(),
+            argument: Some(name.to_string()),
+            index: Some(index),
+        }
+    }
+
+    pub fn code(&self) -> Code {
+        match self {
+            ApiError::UnknownMethod(_) => code::UNKNOWN_METHOD,
+            ApiError::InvalidArguments { .. } => code::INVALID_ARGUMENTS,
+            ApiError::WindowNotFound(_) => code::WINDOW_NOT_FOUND,
+            ApiError::PermissionDenied(_) => code::PERMISSION_DENIED,
+            ApiError::Io(_) => code::IO,
+            ApiError::PlatformUnsupported(_) => code::PLATFORM_UNSUPPORTED,
+            ApiError::Timeout(_) => code::TIMEOUT,
+            ApiError::Cancelled => code::CANCELLED,
+            ApiError::DuplicateRequest(_) => code::DUPLICATE_REQUEST,
+            ApiError::Protocol(_) => code::PROTOCOL,
+            ApiError::Internal(_) => code::INTERNAL,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::UnknownMethod(_) => "unknownMethod",
+            ApiError::InvalidArguments { .. } => "invalidArguments",
+            ApiError::WindowNotFound(_) => "windowNotFound",
+            ApiError::PermissionDenied(_) => "permissionDenied",
+            ApiError::Io(_) => "io",
+            ApiError::PlatformUnsupported(_) => "platformUnsupported",
+            ApiError::Timeout(_) => "timeout",
+            ApiError::Cancelled => "cancelled",
+            ApiError::DuplicateRequest(_) => "duplicateRequest",
+            ApiError::Protocol(_) => "protocol",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    /// Machine readable details sent in the result slot of the response.
+    pub fn details(&self) -> Value {
+        let mut details = json!({ "kind": self.kind() });
+        match self {
+            ApiError::UnknownMethod(method) => details["method"] = json!(method),
+            ApiError::InvalidArguments {
+                argument, index, ..
+            } => {
+                if let Some(argument) = argument {
+                    details["argument"] = json!(argument);
+                }
+                if let Some(index) = index {
+                    details["index"] = json!(index);
+                }
+            }
+            ApiError::WindowNotFound(label) => details["label"] = json!(label),
+            ApiError::Timeout(ms) => details["timeoutMs"] = json!(ms),
+            ApiError::DuplicateRequest(id) => details["id"] = json!(id),
+            _ => {}
+        }
+        details
+    }
+
+    /// Classifies an error returned by an API handler.
+    pub fn from_anyhow(err: &anyhow::Error) -> Self {
+        if let Some(e) = err.downcast_ref::<ApiError>() {
+            return e.clone();
+        }
+        if let Some(e) = err.downcast_ref::<std::io::Error>() {
+            return ApiError::from(e);
+        }
+        if let Some(e) = err.downcast_ref::<serde_json::Error>() {
+            return ApiError::invalid_arguments(e.to_string());
+        }
+        if let Some(tao::error::ExternalError::NotSupported(e)) =
+            err.downcast_ref::<tao::error::ExternalError>()
+        {
+            return ApiError::PlatformUnsupported(e.to_string());
+        }
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::UnknownMethod(method) => write!(f, "Unknown method: {}", method),
+            ApiError::InvalidArguments {
+                message,
+                argument: Some(argument),
+                ..
+            } => write!(f, "Invalid argument {:?}: {}", argument, message),
+            ApiError::InvalidArguments { message, .. } => {
+                write!(f, "Invalid arguments: {}", message)
+            }
+            ApiError::WindowNotFound(label) => write!(f, "Window {:?} not found", label),
+            ApiError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            ApiError::Io(msg) => write!(f, "I/O error: {}", msg),
+            ApiError::PlatformUnsupported(msg) => {
+                write!(f, "Unsupported on this platform: {}", msg)
+            }
+            ApiError::Timeout(ms) => write!(f, "Request timed out after {} ms", ms),
+            ApiError::Cancelled => write!(f, "Request cancelled"),
+            ApiError::DuplicateRequest(id) => write!(f, "Request id {} is already in flight", id),
+            ApiError::Protocol(msg) => write!(f, "Protocol error: {}", msg),
+            ApiError::Internal(msg) => write!(f, "Internal error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<&std::io::Error> for ApiError {
+    fn from(e: &std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => ApiError::PermissionDenied(e.to_string()),
+            std::io::ErrorKind::Unsupported => ApiError::PlatformUnsupported(e.to_string()),
+            _ => ApiError::Io(e.to_string()),
+        }
+    }
+}
@@ -14,18 +14,23 @@ use crate::utils::FrameEventLoopBuilder;
 mod api;
 mod api_manager;
 mod assets;
+mod binary;
 mod connections;
 mod context;
 mod core;
+mod error;
+mod schema;
 mod utils;
 mod window;
 
 #[pyfunction]
+#[pyo3(signature = (config, sock_cfg, uds_name, close_event, server_cfg=None))]
 fn create_webframe(
     config: String,
     sock_cfg: Option<String>,
     uds_name: String,
     close_event: Py<PyAny>,
+    server_cfg: Option<String>,
 ) -> Result<()> {
     let options: &pyorion_options::window::WindowOptions = &serde_json::from_str(&config)?;
 
@@ -34,9 +39,21 @@ fn create_webframe(
         None => None,
     };
 
+    let server_cfg: connections::ServerConfig = match server_cfg {
+        Some(s) => serde_json::from_str(&s)?,
+        None => connections::ServerConfig::default(),
+    };
+
     let mut event_loop = FrameEventLoopBuilder::with_user_event().build();
-    let app = core::App::new(&mut event_loop, sock_cfg_json, options, uds_name)?;
-    app.run(event_loop, close_event)
+    let app = core::App::new(
+        &mut event_loop,
+        sock_cfg_json,
+        options,
+        uds_name,
+        close_event,
+        server_cfg,
+    )?;
+    app.run(event_loop)
 }
 
 pub fn get_pyorion_version() -> &'static str {
@@ -61,5 +78,7 @@ fn _pyorion(m: &Bound<'_, PyModule>) -> PyResult<()> {
         crate::connections::send_event_over_platform,
         m
     )?)?;
+    m.add_class::<crate::connections::client::IpcClient>()?;
+    m.add_class::<crate::connections::client::ResponseStream>()?;
     Ok(())
 }
@@ -0,0 +1,133 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! JSON Schema (draft 2020-12) for the types used in handler signatures.
+//!
+//! `#[api]` captures [`arg_schema`] and [`result_schema`] for every
+//! parameter and result, so the schemas come from the `JsonSchema` impls of
+//! the types themselves. Subschemas are inlined, so each schema stands on
+//! its own, and `Option<T>` is reported as `anyOf: [T, {"type": "null"}]`.
+
+use schemars::{
+    generate::{Contract, SchemaSettings},
+    transform::{RecursiveTransform, Transform},
+    JsonSchema, Schema,
+};
+use serde_json::{json, Value};
+
+/// Schema of a parameter, i.e. of what the handler accepts.
+pub fn arg_schema<T: JsonSchema>() -> Value {
+    schema_for::<T>(Contract::Deserialize)
+}
+
+/// Schema of a result, i.e. of what the handler sends back.
+pub fn result_schema<T: JsonSchema>() -> Value {
+    schema_for::<T>(Contract::Serialize)
+}
+
+fn schema_for<T: JsonSchema>(contract: Contract) -> Value {
+    let mut generator = SchemaSettings::draft2020_12()
+        .with(|settings| {
+            settings.inline_subschemas = true;
+            settings.contract = contract;
+        })
+        .into_generator();
+    let mut schema = generator.subschema_for::<T>();
+    RecursiveTransform(null_as_any_of).transform(&mut schema);
+    // `true` accepts anything as well, `{}` is what clients expect
+    schema.ensure_object();
+    schema.to_value()
+}
+
+/// Rewrites `{"type": [T, "null"], ..}` into
+/// `{"anyOf": [{"type": T, ..}, {"type": "null"}]}`.
+fn null_as_any_of(schema: &mut Schema) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    let Some(Value::Array(types)) = obj.get_mut("type") else {
+        return;
+    };
+    let null = Value::from("null");
+    if types.len() < 2 || !types.contains(&null) {
+        return;
+    }
+    types.retain(|ty| *ty != null);
+    if types.len() == 1 {
+        let ty = types.remove(0);
+        obj.insert("type".to_string(), ty);
+    }
+    if let Some(Value::Array(values)) = obj.get_mut("enum") {
+        values.retain(|value| !value.is_null());
+    }
+    let inner = std::mem::take(obj);
+    obj.insert("anyOf".to_string(), json!([inner, { "type": "null" }]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, JsonSchema)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    enum Unit {
+        Logical,
+        Physical,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct Size {
+        width: Option<u32>,
+        unit: Unit,
+    }
+
+    #[test]
+    fn describes_plain_types() {
+        assert_eq!(arg_schema::<String>(), json!({ "type": "string" }));
+        assert_eq!(arg_schema::<bool>(), json!({ "type": "boolean" }));
+        assert_eq!(arg_schema::<Value>(), json!({}));
+        assert_eq!(
+            arg_schema::<Vec<String>>(),
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+    }
+
+    #[test]
+    fn reports_options_as_any_of_null() {
+        assert_eq!(
+            arg_schema::<Option<String>>(),
+            json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+        assert_eq!(
+            arg_schema::<Option<Unit>>(),
+            json!({
+                "anyOf": [
+                    { "type": "string", "enum": ["logical", "physical"] },
+                    { "type": "null" },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn inlines_structs_and_enums() {
+        let schema = arg_schema::<Size>();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], json!(["unit"]));
+        assert_eq!(
+            schema["properties"]["width"]["anyOf"][1],
+            json!({ "type": "null" })
+        );
+        assert_eq!(
+            schema["properties"]["unit"]["enum"],
+            json!(["logical", "physical"])
+        );
+        let text = schema.to_string();
+        assert!(!text.contains("$ref") && !text.contains("nullable"));
+    }
+}
@@ -1,11 +1,14 @@
 use crate::api_manager::{ApiRequest, ApiResponse};
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use serde_json::Value;
 use std::fmt;
 use std::path::Path;
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
 };
 use tao::event_loop::{EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget};
 
@@ -17,11 +20,25 @@ pub type FrameEventLoopBuilder = EventLoopBuilder<UserEvent>;
 pub type FrameEventLoopProxy = EventLoopProxy<UserEvent>;
 #[allow(dead_code)]
 pub type FrameWindowTarget = EventLoopWindowTarget<UserEvent>;
+/// Identifies one client connection of the IPC server.
+pub type ConnectionId = u64;
+/// Request ids are chosen by the client, so they are only unique per connection.
+pub type RequestKey = (ConnectionId, u64);
 #[allow(dead_code)]
-pub type PendingMap = Arc<Mutex<HashMap<u8, tokio::sync::oneshot::Sender<ApiResponse>>>>;
+pub type PendingMap = Arc<Mutex<HashMap<RequestKey, tokio::sync::oneshot::Sender<ApiResponse>>>>;
+/// Work an async handler sends to the main thread, see
+/// [`AppHandle::run_on_main`](crate::api_manager::AppHandle::run_on_main).
+pub type MainTask = Box<
+    dyn FnOnce(&Arc<crate::core::App>, &FrameWindowTarget, &mut tao::event_loop::ControlFlow)
+        + Send,
+>;
 #[allow(dead_code)]
 pub enum UserEvent {
-    Request(ApiRequest),
+    Request(ConnectionId, ApiRequest),
+    /// A message posted by the page of a webview, by label, see
+    /// [`ipc`](crate::window::ipc).
+    WebviewIpc(String, String),
+    Dispatch(MainTask),
     Shutdown,
 }
 #[allow(dead_code)]
@@ -34,27 +51,22 @@ pub fn arc<T>(t: T) -> Arc<T> {
 pub fn arc_mut<T>(t: T) -> ArcMut<T> {
     Arc::new(Mutex::new(t))
 }
+/// Lock-free source of 64-bit ids, usable from a `static`.
 #[allow(dead_code)]
 pub struct IdCounter {
-    next_id: u8,
+    next_id: AtomicU64,
 }
 
 impl IdCounter {
     #[allow(dead_code)]
-    pub fn new() -> Self {
-        Self { next_id: 0 }
+    pub const fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+        }
     }
     #[allow(dead_code)]
-    pub fn next<T>(&mut self, excludes: &HashMap<u8, T>) -> Result<u8> {
-        for _ in 0..u8::MAX {
-            let id = self.next_id;
-            if excludes.contains_key(&id) {
-                self.next_id += 1;
-                continue;
-            }
-            return Ok(id);
-        }
-        Err(anyhow!("Failed to find a valid id."))
+    pub fn next(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
     }
 }
 
@@ -354,34 +366,6 @@ impl MimeType {
     }
 }
 
-#[allow(dead_code)]
-fn get_wry_response(
-    request: wry::http::Request<Vec<u8>>,
-    index_page: Option<String>, // Default index page filename
-    root: &str,
-) -> Result<wry::http::Response<Vec<u8>>, Box<dyn std::error::Error>> {
-    let path = request.uri().path();
-    let root = std::path::PathBuf::from(root);
-    let file_path: String = if path == "/" {
-        match index_page {
-            Some(index) => index,
-            None => "index.html".to_string(),
-        }
-    } else {
-        path[1..].to_string()
-    };
-
-    let content = std::fs::read(std::fs::canonicalize(root.join(&file_path))?)?;
-
-    // Dynamically determine MIME
-    let mime_type = MimeType::parse_from_uri(&file_path).to_string();
-    // Create and return the HTTP response
-    wry::http::Response::builder()
-        .header(wry::http::header::CONTENT_TYPE, mime_type)
-        .body(content)
-        .map_err(Into::into)
-}
-
 fn split_root_and_index(input: &str) -> Result<(String, String), String> {
     if input.trim().is_empty() {
         return Err("Path must not be empty".to_string());
@@ -419,6 +403,8 @@ fn split_root_and_index(input: &str) -> Result<(String, String), String> {
 pub fn render_protocol<'a>(
     mut builder: wry::WebViewBuilder<'a>,
     root_path: Option<String>,
+    symlinks: pyorion_options::window::SymlinkPolicy,
+    rt: tokio::runtime::Handle,
 ) -> wry::WebViewBuilder<'a> {
     let main_root = root_path.unwrap_or_else(|| ".".to_string());
 
@@ -441,22 +427,16 @@ pub fn render_protocol<'a>(
     builder = builder.with_asynchronous_custom_protocol(
         "wry".into(),
         move |_webview_id, request, responder| {
-            let response = get_wry_response(
-                request,
-                Some(index_page.clone()), // Standard Index
-                &main_root_clone,         // Root directory
-            );
-
-            match response {
-                Ok(http_response) => responder.respond(http_response),
-                Err(e) => responder.respond(
-                    wry::http::Response::builder()
-                        .header(wry::http::header::CONTENT_TYPE, "text/plain")
-                        .status(500)
-                        .body(e.to_string().as_bytes().to_vec())
-                        .unwrap(),
-                ),
-            }
+            let root = main_root_clone.clone(); // Root directory
+            let index = index_page.clone(); // Standard Index
+
+            // disk reads must not stall the event loop, which runs this;
+            // tokio's blocking pool caps the threads they take
+            rt.spawn_blocking(move || {
+                responder.respond(crate::assets::protocol::respond(
+                    &request, &root, &index, symlinks,
+                ))
+            });
         },
     );
 
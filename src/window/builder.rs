@@ -8,7 +8,7 @@ use tao::{
     window::{Window, WindowBuilder},
 };
 
-use crate::utils::{render_protocol, FrameWindowTarget};
+use crate::utils::{render_protocol, FrameEventLoopProxy, FrameWindowTarget};
 
 #[allow(dead_code)]
 pub struct FrameBuilder;
@@ -103,24 +103,30 @@ impl FrameBuilder {
     pub fn build_webview(
         window: &tao::window::Window,
         options: &WebViewOptions,
+        label: &str,
         sock_cfg: Option<crate::assets::WebSocketConfig>,
+        proxy: FrameEventLoopProxy,
+        rt: tokio::runtime::Handle,
     ) -> anyhow::Result<wry::WebView> {
-        // websocket_config
-        let mut builder = wry::WebViewBuilder::new();
+        // `invoke` talks to the event loop directly, see `ipc`
+        let mut builder = wry::WebViewBuilder::new()
+            .with_initialization_script(crate::assets::_COMMAND_SCRIPT)
+            .with_initialization_script(crate::assets::_EVENTS_SCRIPT)
+            .with_ipc_handler(super::ipc::handler(
+                proxy,
+                label.to_string(),
+                options.render_protocol.clone(),
+            ));
 
+        // websocket_config
         if let Some(conf) = sock_cfg {
             let socket_conf = crate::assets::websocket_config(conf)?;
-            builder = builder
-                .with_initialization_script(socket_conf)
-                .with_initialization_script(crate::assets::_COMMAND_SCRIPT);
-        }
-        if let Some(label) = &options.label {
-            builder = builder.with_id(label.as_str());
-        } else {
-            builder = builder.with_id("root_webview");
+            builder = builder.with_initialization_script(socket_conf);
         }
+        builder = builder.with_id(label);
         let binding = &options.render_protocol;
-        let mut builder = render_protocol(builder, binding.clone());
+        let symlinks = options.asset_symlinks.unwrap_or_default();
+        let mut builder = render_protocol(builder, binding.clone(), symlinks, rt);
         if let Some(v) = options.transparent {
             builder = builder.with_transparent(v);
         }
@@ -0,0 +1,213 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Direct IPC between a webview and the event loop.
+//!
+//! `invoke.js` posts `{"id", "cmd", "args", "label"?}` messages through
+//! `window.ipc`. Methods registered with the [`ApiManager`] are answered
+//! right here, event-loop handlers within the same turn and async ones on
+//! the runtime. Anything else is taken for a Python `@command` and pushed
+//! to the Python process as a [`INVOKE_EVENT`] event, whose answer comes
+//! back through `webview.respond`. Either way the page gets the usual
+//! `[id, code, msg, result]` response through
+//! `window.__PYORION_IPC__.resolve`.
+//!
+//! Only the app's own pages may call in: those of the `wry://` protocol and
+//! of the configured URL's origin.
+//!
+//! [`ApiManager`]: crate::api_manager::ApiManager
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::{
+    api_manager::{ApiRequest, ApiResponse, AppHandle, ChunkSink},
+    core::App,
+    error::ApiError,
+    utils::{FrameEventLoopProxy, FrameWindowTarget, UserEvent},
+};
+
+/// Topic Python commands are pushed under; Python's command loop
+/// subscribes to it.
+pub const INVOKE_EVENT: &str = "webview.invoke";
+
+/// One call posted by a page. Without a label it targets the calling window.
+#[derive(Deserialize)]
+struct Invoke {
+    id: u64,
+    cmd: String,
+    #[serde(default)]
+    args: Value,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// IPC handler of the webview `label`, showing the page `root`. Messages of
+/// trusted pages are handed to the event loop as they are.
+pub fn handler(
+    proxy: FrameEventLoopProxy,
+    label: String,
+    root: Option<String>,
+) -> impl Fn(wry::http::Request<String>) + 'static {
+    move |request| {
+        if !is_trusted(request.uri(), root.as_deref()) {
+            eprintln!("[ipc] Ignoring message from {}", request.uri());
+            return;
+        }
+        let _ = proxy.send_event(UserEvent::WebviewIpc(label.clone(), request.into_body()));
+    }
+}
+
+/// Whether the page at `uri` belongs to the app: served by the `wry://`
+/// protocol (`http://wry.localhost` on Windows), loaded from the origin of
+/// a configured URL or, for inline HTML, without any origin.
+pub(crate) fn is_trusted(uri: &wry::http::Uri, root: Option<&str>) -> bool {
+    if uri.scheme_str() == Some("wry") || uri.host() == Some("wry.localhost") {
+        return true;
+    }
+    let root = root.unwrap_or(".");
+    if root.starts_with("http://") || root.starts_with("https://") {
+        return root.parse::<wry::http::Uri>().is_ok_and(|root| {
+            root.scheme() == uri.scheme()
+                && root.host() == uri.host()
+                && root.port_u16() == uri.port_u16()
+        });
+    }
+    // `about:blank` parses as a host named `about`
+    is_inline(Some(root)) && (uri.host().is_none() || uri == "about:blank")
+}
+
+/// Whether `root` is inline HTML rather than a URL or asset folder.
+pub(crate) fn is_inline(root: Option<&str>) -> bool {
+    root.is_some_and(|root| root.contains("<html>") || root.contains("<!DOCTYPE html>"))
+}
+
+/// Serves one message posted by the webview `label`.
+pub fn serve(
+    app: &Arc<App>,
+    label: String,
+    body: &str,
+    target: &FrameWindowTarget,
+    flow: &mut tao::event_loop::ControlFlow,
+) {
+    let invoke: Invoke = match serde_json::from_str(body) {
+        Ok(invoke) => invoke,
+        // without an id there is nobody to answer
+        Err(e) => {
+            eprintln!("[ipc] Ignoring malformed message: {}", e);
+            return;
+        }
+    };
+    let target_label = invoke.label.unwrap_or_else(|| label.clone());
+    let request = json!([invoke.id, invoke.cmd, invoke.args, target_label]);
+    let req: ApiRequest = match serde_json::from_value(request.clone()) {
+        Ok(req) => req,
+        Err(e) => {
+            let err = ApiError::Protocol(e.to_string());
+            reply(app, Some(&label), &ApiResponse::error(invoke.id, &err));
+            return;
+        }
+    };
+
+    if let Some(api) = app.async_api(&req.1) {
+        if api.stream {
+            let err = ApiError::invalid_arguments(format!(
+                "{} streams its result and is only served over the IPC server",
+                req.1
+            ));
+            reply(app, Some(&label), &req.fail(&err));
+            return;
+        }
+        let handle = AppHandle::new(app.proxy.clone());
+        let fut = (api.run)(handle.clone(), req.clone(), ChunkSink::default());
+        app.rt.spawn(async move {
+            // a panicking handler only fails its own request
+            let resp = match tokio::spawn(fut).await {
+                Ok(resp) => resp,
+                Err(_) => req.fail(&ApiError::Internal(format!(
+                    "handler for {} panicked",
+                    req.1
+                ))),
+            };
+            let _ = handle
+                .run_on_main(move |app, _, _| {
+                    reply(app, Some(&label), &resp);
+                    Ok(())
+                })
+                .await;
+        });
+        return;
+    }
+
+    let resp = match app.api_manager() {
+        Ok(mut manager) if manager.has_api(&req.1) => Some(
+            manager
+                .call(req.clone(), target, flow)
+                .unwrap_or_else(|err| req.fail(&ApiError::from_anyhow(&err))),
+        ),
+        Ok(_) => None,
+        Err(err) => Some(req.fail(&ApiError::from_anyhow(&err))),
+    };
+    if let Some(resp) = resp {
+        reply(app, Some(&label), &resp);
+        return;
+    }
+
+    // a Python command; nobody answers while Python is not listening
+    if !app.events().has_subscribers(INVOKE_EVENT) {
+        let err = ApiError::UnknownMethod(req.1.clone());
+        reply(app, Some(&label), &req.fail(&err));
+        return;
+    }
+    let payload = json!({ "id": req.0, "cmd": req.1, "args": request[2] });
+    app.events().emit(INVOKE_EVENT, Some(&label), payload);
+}
+
+/// Passes a response to the page of the webview `label`, the main one when
+/// `None`.
+pub fn reply(app: &App, label: Option<&str>, resp: &ApiResponse) {
+    let script = match serde_json::to_string(resp) {
+        Ok(resp) => format!(
+            "window.__PYORION_IPC__ && window.__PYORION_IPC__.resolve({})",
+            resp
+        ),
+        Err(e) => {
+            eprintln!("[ipc] Failed to encode response: {}", e);
+            return;
+        }
+    };
+    let webview = app.app_context().and_then(|ctx| ctx.get_webview(label));
+    let result = webview.and_then(|webview| Ok(webview.evaluate_script(&script)?));
+    if let Err(e) = result {
+        eprintln!("[ipc] Failed to answer {:?}: {}", label, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted(uri: &str, root: Option<&str>) -> bool {
+        is_trusted(&uri.parse().unwrap(), root)
+    }
+
+    #[test]
+    fn trusts_only_the_apps_own_pages() {
+        assert!(trusted("wry://localhost/index.html", None));
+        assert!(trusted("http://wry.localhost/index.html", Some("./web")));
+
+        let root = Some("http://localhost:5173/");
+        assert!(trusted("http://localhost:5173/app", root));
+        assert!(!trusted("http://localhost:8080/app", root));
+        assert!(!trusted("https://evil.example/", root));
+
+        // inline HTML has no origin; a navigation away from it does
+        let inline = Some("<!DOCTYPE html><p>hi</p>");
+        assert!(trusted("about:blank", inline));
+        assert!(trusted("/", inline));
+        assert!(!trusted("https://evil.example/", inline));
+        assert!(!trusted("https://evil.example/", None));
+    }
+}
@@ -6,17 +6,25 @@ use pyorion_options::window::WindowOptions;
 use tao::window::{Window, WindowId};
 use wry::WebView;
 
-use crate::{utils::FrameWindowTarget, window::builder::FrameBuilder};
+use crate::{
+    utils::{FrameEventLoopProxy, FrameWindowTarget},
+    window::builder::FrameBuilder,
+};
 
 pub(crate) mod builder;
+pub(crate) mod ipc;
 
 pub fn create_frame(
     target: &FrameWindowTarget,
     options: &WindowOptions,
+    label: &str,
     sock_cfg: Option<crate::assets::WebSocketConfig>,
+    proxy: FrameEventLoopProxy,
+    rt: tokio::runtime::Handle,
 ) -> anyhow::Result<(WindowId, Window, WebView)> {
     let window = FrameBuilder::build_window(target, options)?;
     let id = window.id();
-    let webview = FrameBuilder::build_webview(&window, &options.webview, sock_cfg)?;
+    let webview =
+        FrameBuilder::build_webview(&window, &options.webview, label, sock_cfg, proxy, rt)?;
     Ok((id, window, webview))
 }